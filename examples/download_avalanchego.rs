@@ -10,8 +10,9 @@ async fn main() -> io::Result<()> {
         env_logger::Env::default().filter_or(env_logger::DEFAULT_FILTER_ENV, "info"),
     );
 
-    let avalanchego_path = github::download_latest(None, None).await.unwrap();
-    log::info!("avalanchego path: {}", avalanchego_path);
+    let outcome = github::download_latest(None, None).await.unwrap();
+    log::info!("avalanchego path: {}", outcome.binary_path.display());
+    log::info!("outcome: {:?}", outcome);
 
     Ok(())
 }
@@ -11,10 +11,11 @@ async fn main() -> io::Result<()> {
     );
 
     let subnet_evm_path = random_manager::tmp_path(10, None).unwrap();
-    github::download_latest(None, None, &subnet_evm_path)
+    let outcome = github::download_latest(None, None, &subnet_evm_path)
         .await
         .unwrap();
     log::info!("subnet_evm path: {}", subnet_evm_path);
+    log::info!("outcome: {:?}", outcome);
 
     Ok(())
 }
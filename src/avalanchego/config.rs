@@ -0,0 +1,307 @@
+use std::{
+    collections::HashSet,
+    fs,
+    io::{self, Error, ErrorKind},
+    path::Path,
+};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{install::write_file_atomic, network::Network};
+
+/// Default file name of the node config, written next to the installed binary.
+pub const DEFAULT_CONFIG_FILE_NAME: &str = "config.json";
+
+/// Default "--http-port", the same on every network.
+pub const DEFAULT_HTTP_PORT: u16 = 9650;
+/// Default "--staking-port", the same on every network.
+/// Run nodes of different networks on the same host with different ports.
+pub const DEFAULT_STAKING_PORT: u16 = 9651;
+
+/// Log levels that "avalanchego" accepts.
+pub const LOG_LEVELS: &[&str] = &[
+    "off", "fatal", "error", "warn", "info", "trace", "debug", "verbo",
+];
+
+/// Represents the "avalanchego" node config file, passed via "--config-file".
+/// ref. <https://docs.avax.network/nodes/configure/avalanchego-config-flags>
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct Config {
+    pub network_id: Network,
+
+    pub http_host: String,
+    pub http_port: u16,
+    pub staking_port: u16,
+
+    /// Directory of the VM plugin binaries.
+    /// See "avalanchego::get_plugin_dir".
+    pub plugin_dir: String,
+    pub data_dir: String,
+    pub chain_config_dir: String,
+
+    pub log_level: String,
+
+    /// Comma-separated beacon IPs, left empty to bootstrap alone.
+    /// Leave none to use the beacons built into "avalanchego".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bootstrap_ips: Option<String>,
+    /// Comma-separated beacon node IDs, in the same order as "bootstrap_ips".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bootstrap_ids: Option<String>,
+
+    /// Subnet IDs to track, comma-separated in the config file.
+    #[serde(
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        serialize_with = "serialize_comma_separated",
+        deserialize_with = "deserialize_comma_separated"
+    )]
+    pub track_subnets: Vec<String>,
+}
+
+fn serialize_comma_separated<S>(v: &[String], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str(&v.join(","))
+}
+
+fn deserialize_comma_separated<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    Ok(s.split(',')
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .collect())
+}
+
+impl Config {
+    /// Creates the mainnet config for the "avalanchego" binary
+    /// and the data directory, with the default ports.
+    pub fn new<P: AsRef<Path>, Q: AsRef<Path>>(avalanchego_bin: P, data_dir: Q) -> Self {
+        Self::new_for_network(Network::Mainnet, avalanchego_bin, data_dir)
+    }
+
+    /// Creates the config with the defaults of the network preset,
+    /// and the default ports, which do not depend on the network.
+    pub fn new_for_network<P: AsRef<Path>, Q: AsRef<Path>>(
+        network: Network,
+        avalanchego_bin: P,
+        data_dir: Q,
+    ) -> Self {
+        let data_dir = data_dir.as_ref();
+        let (bootstrap_ips, bootstrap_ids) = match network.bootstrap() {
+            Some((ips, ids)) => (Some(ips), Some(ids)),
+            None => (None, None),
+        };
+        Self {
+            network_id: network,
+            http_host: String::from("127.0.0.1"),
+            http_port: DEFAULT_HTTP_PORT,
+            staking_port: DEFAULT_STAKING_PORT,
+            plugin_dir: super::get_plugin_dir(avalanchego_bin),
+            data_dir: data_dir.display().to_string(),
+            chain_config_dir: data_dir
+                .join("configs")
+                .join("chains")
+                .display()
+                .to_string(),
+            log_level: String::from("info"),
+            bootstrap_ips,
+            bootstrap_ids,
+            track_subnets: Vec::new(),
+        }
+    }
+
+    /// Returns an error if the config is invalid.
+    pub fn validate(&self) -> io::Result<()> {
+        let count = |v: &Option<String>| {
+            v.as_deref()
+                .unwrap_or_default()
+                .split(',')
+                .filter(|s| !s.trim().is_empty())
+                .count()
+        };
+        if self.bootstrap_ips.is_some() != self.bootstrap_ids.is_some()
+            || count(&self.bootstrap_ips) != count(&self.bootstrap_ids)
+        {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "bootstrap-ips and bootstrap-ids must have the same number of entries",
+            ));
+        }
+        if self.http_port == 0 || self.staking_port == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "http-port and staking-port must be non-zero",
+            ));
+        }
+        if self.http_port == self.staking_port {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "http-port and staking-port must differ (both {})",
+                    self.http_port
+                ),
+            ));
+        }
+        for (name, dir) in [
+            ("plugin-dir", &self.plugin_dir),
+            ("data-dir", &self.data_dir),
+            ("chain-config-dir", &self.chain_config_dir),
+        ] {
+            if !Path::new(dir).is_absolute() {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("{} must be an absolute path, got '{}'", name, dir),
+                ));
+            }
+        }
+        if !LOG_LEVELS.contains(&self.log_level.to_lowercase().as_str()) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "unknown log-level '{}' (expected one of {:?})",
+                    self.log_level, LOG_LEVELS
+                ),
+            ));
+        }
+
+        let mut seen = HashSet::new();
+        for subnet_id in self.track_subnets.iter() {
+            if subnet_id.is_empty() || !subnet_id.chars().all(|c| c.is_ascii_alphanumeric()) {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("invalid subnet ID '{}' in track-subnets", subnet_id),
+                ));
+            }
+            if !seen.insert(subnet_id) {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("duplicate subnet ID '{}' in track-subnets", subnet_id),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Encodes the config to JSON.
+    pub fn encode_json(&self) -> io::Result<String> {
+        serde_json::to_string_pretty(self).map_err(|e| {
+            Error::new(
+                ErrorKind::Other,
+                format!("failed to serialize config {}", e),
+            )
+        })
+    }
+
+    /// Validates and saves the config to the file atomically.
+    pub fn sync<P: AsRef<Path>>(&self, file_path: P) -> io::Result<()> {
+        self.validate()?;
+        let file_path = file_path.as_ref();
+        log::info!("syncing config to {}", file_path.display());
+        if let Some(parent_dir) = file_path.parent() {
+            fs::create_dir_all(parent_dir)?;
+        }
+        write_file_atomic(file_path, self.encode_json()?.as_bytes())
+    }
+
+    /// Loads the config from the file.
+    pub fn load<P: AsRef<Path>>(file_path: P) -> io::Result<Self> {
+        let file_path = file_path.as_ref();
+        let d = fs::read(file_path)?;
+        serde_json::from_slice(&d).map_err(|e| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("failed to decode config {} ({})", file_path.display(), e),
+            )
+        })
+    }
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-installer --lib -- avalanchego::config::test_config_network --exact --show-output
+#[test]
+fn test_config_network() {
+    let cfg = Config::new_for_network(
+        Network::Local,
+        "/opt/avalanche/avalanchego",
+        "/var/lib/avalanchego",
+    );
+    cfg.validate().unwrap();
+    let encoded = cfg.encode_json().unwrap();
+    assert!(encoded.contains("\"network-id\": \"local\""));
+    assert!(encoded.contains("\"bootstrap-ips\": \"\""));
+
+    let cfg = Config::new("/opt/avalanche/avalanchego", "/var/lib/avalanchego");
+    assert!(!cfg.encode_json().unwrap().contains("bootstrap-ips"));
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-installer --lib -- avalanchego::config::test_config_validate --exact --show-output
+#[test]
+fn test_config_validate() {
+    let cfg = Config::new("/opt/avalanche/avalanchego", "/var/lib/avalanchego");
+    cfg.validate().unwrap();
+
+    let rejected = |update: &dyn Fn(&mut Config), expected: &str| {
+        let mut cfg = cfg.clone();
+        update(&mut cfg);
+        let err = cfg.validate().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        assert!(err.to_string().contains(expected), "{}", err);
+    };
+
+    // port collision
+    rejected(
+        &|c| c.staking_port = c.http_port,
+        "http-port and staking-port must differ (both 9650)",
+    );
+    rejected(&|c| c.http_port = 0, "must be non-zero");
+
+    // bootstrap IPs and IDs count mismatch
+    rejected(
+        &|c| {
+            c.bootstrap_ips = Some(String::from("10.0.0.1:9651,10.0.0.2:9651"));
+            c.bootstrap_ids = Some(String::from("NodeID-7Xhw2mDxuDS44j42TCB6U5579esbSt3Lg"));
+        },
+        "same number of entries",
+    );
+    rejected(
+        &|c| c.bootstrap_ips = Some(String::from("10.0.0.1:9651")),
+        "same number of entries",
+    );
+
+    // invalid log level
+    rejected(
+        &|c| c.log_level = String::from("verbose"),
+        "unknown log-level",
+    );
+
+    // bad track-subnets entries
+    rejected(
+        &|c| c.track_subnets = vec![String::new()],
+        "invalid subnet ID '' in track-subnets",
+    );
+    rejected(
+        &|c| c.track_subnets = vec![String::from("abc,def")],
+        "invalid subnet ID 'abc,def' in track-subnets",
+    );
+    rejected(
+        &|c| c.track_subnets = vec![String::from("abc"), String::from("abc")],
+        "duplicate subnet ID 'abc' in track-subnets",
+    );
+
+    rejected(
+        &|c| c.data_dir = String::from("data"),
+        "data-dir must be an absolute path",
+    );
+
+    let mut cfg = cfg.clone();
+    cfg.log_level = String::from("DEBUG");
+    cfg.bootstrap_ips = Some(String::from("10.0.0.1:9651"));
+    cfg.bootstrap_ids = Some(String::from("NodeID-7Xhw2mDxuDS44j42TCB6U5579esbSt3Lg"));
+    cfg.track_subnets = vec![String::from("abc"), String::from("def")];
+    cfg.validate().unwrap();
+}
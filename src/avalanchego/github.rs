@@ -1,24 +1,45 @@
 use std::{
-    env, fmt,
     fs::{self, File},
-    io::{self, copy, Cursor, Error, ErrorKind},
+    io::{self, Error, ErrorKind},
     os::unix::fs::PermissionsExt,
     path::Path,
+    time::Instant,
 };
 
-use compress_manager::DirDecoder;
-use tokio::time::{sleep, Duration};
+use crate::{
+    extract::{self, ArchiveKind},
+    install::{download_archive, InstallReceipt},
+    lock::{self, Pin},
+    project::Project,
+    workspace::Workspace,
+};
+
+pub use crate::{
+    download::download_file,
+    install::{InstallOutcome, Options},
+    platform::{Arch, Os, Platform},
+};
 
 /// Downloads the latest "avalanchego" from the github release page.
-pub async fn download_latest(arch: Option<Arch>, os: Option<Os>) -> io::Result<String> {
+pub async fn download_latest(arch: Option<Arch>, os: Option<Os>) -> io::Result<InstallOutcome> {
     download(arch, os, None).await
 }
 
 /// ref. <https://github.com/ava-labs/avalanchego/releases>
-pub const DEFAULT_TAG_NAME: &str = "v1.10.3";
+pub const DEFAULT_TAG_NAME: &str = Project::AvalancheGo.default_tag_name();
+
+/// Platforms with the official "avalanchego" release builds.
+/// ref. <https://github.com/ava-labs/avalanchego/releases>
+pub const SUPPORTED_PLATFORMS: &[Platform] = &[
+    Platform::new(Os::Linux, Arch::Amd64),
+    Platform::new(Os::Linux, Arch::Arm64),
+    Platform::new(Os::MacOs, Arch::Amd64),
+    Platform::new(Os::MacOs, Arch::Arm64),
+    Platform::new(Os::Windows, Arch::Amd64),
+];
 
 /// Downloads the official "avalanchego" binaries from the GitHub release page.
-/// Returns the path to the binary path.
+/// Returns the install outcome with the path to the binary.
 ///
 /// Leave "release_tag" none to download the latest.
 ///
@@ -30,135 +51,189 @@ pub async fn download(
     arch: Option<Arch>,
     os: Option<Os>,
     release_tag: Option<String>,
-) -> io::Result<String> {
-    // e.g., "v1.10.0"
-    let tag_name = if let Some(v) = release_tag {
-        // "https://github.com/ava-labs/avalanchego/releases" doesn't have "latest" tag
-        if v.eq("latest") {
-            log::warn!("falling back 'latest' to {DEFAULT_TAG_NAME}");
-            DEFAULT_TAG_NAME.to_owned()
-        } else {
-            v
-        }
-    } else {
-        log::info!("fetching the latest git tags");
-        let mut release_info = crate::github::ReleaseResponse::default();
-        for round in 0..10 {
-            let info = match crate::github::fetch_latest_release("ava-labs", "avalanchego").await {
-                Ok(v) => v,
-                Err(e) => {
-                    log::warn!(
-                        "failed fetch_latest_release {} -- retrying {}...",
-                        e,
-                        round + 1
-                    );
-                    sleep(Duration::from_secs((round + 1) * 3)).await;
-                    continue;
-                }
-            };
-
-            release_info = info;
-            if release_info.tag_name.is_some() {
-                break;
-            }
-
-            log::warn!("release_info.tag_name is None -- retrying {}...", round + 1);
-            sleep(Duration::from_secs((round + 1) * 3)).await;
-        }
+) -> io::Result<InstallOutcome> {
+    download_with_options(&Options {
+        arch,
+        os,
+        release_tag,
+        ..Default::default()
+    })
+    .await
+}
 
-        if release_info.tag_name.is_none() {
-            log::warn!("release_info.tag_name not found -- defaults to {DEFAULT_TAG_NAME}");
-            release_info.tag_name = Some(DEFAULT_TAG_NAME.to_string());
-        }
+/// Same as "download" but with the options.
+/// The unpacked files are left in the workspace directory for the caller.
+pub async fn download_with_options(opts: &Options) -> io::Result<InstallOutcome> {
+    let (mut outcome, workspace) = download_and_unpack(opts, None).await?;
+    if opts.verify_version {
+        outcome = outcome.verify_version_blocking().await?;
+    }
+    lock::record_pin(opts.lock_file.as_deref(), &outcome)?;
+    workspace.persist();
+    Ok(outcome)
+}
 
-        if release_info.prerelease {
-            log::warn!(
-                "latest release '{}' is prerelease, falling back to default tag name '{}'",
-                release_info.tag_name.unwrap(),
-                DEFAULT_TAG_NAME
-            );
-            DEFAULT_TAG_NAME.to_string()
-        } else {
-            release_info.tag_name.unwrap()
-        }
-    };
+/// Downloads the official "avalanchego" binaries from the GitHub release page,
+/// and installs them to the "target_dir" with the same layout as the release archive.
+/// The binary is installed as "{target_dir}/avalanchego", and the "plugins" directory
+/// (if the release ships one) as "{target_dir}/plugins", which is where
+/// "avalanchego::get_plugin_dir" expects it. Existing plugins in the
+/// "target_dir" are kept, unless the release ships a plugin with the same name.
+///
+/// See "download" for the other parameters.
+pub async fn install(
+    arch: Option<Arch>,
+    os: Option<Os>,
+    release_tag: Option<String>,
+    target_dir: &str,
+) -> io::Result<InstallOutcome> {
+    install_with_options(
+        &Options {
+            arch,
+            os,
+            release_tag,
+            ..Default::default()
+        },
+        target_dir,
+    )
+    .await
+}
 
-    // ref. <https://github.com/ava-labs/avalanchego/releases>
-    log::info!(
-        "detecting arch and platform for the release version tag {}",
-        tag_name
-    );
-    let arch = {
-        if arch.is_none() {
-            match env::consts::ARCH {
-                "x86_64" => String::from("amd64"),
-                "aarch64" => String::from("arm64"),
-                _ => String::from(""),
-            }
-        } else {
-            let arch = arch.unwrap();
-            arch.to_string()
-        }
-    };
+/// Same as "install" but with the options.
+pub async fn install_with_options(opts: &Options, target_dir: &str) -> io::Result<InstallOutcome> {
+    let started = Instant::now();
+    crate::workspace::ensure_executable(target_dir)?;
+    let (mut outcome, workspace) = download_and_unpack(opts, Some(Path::new(target_dir))).await?;
 
-    // TODO: handle Apple arm64 when the official binary is available
-    // ref. <https://github.com/ava-labs/avalanchego/releases>
-    let (file_name, dir_decoder) = {
-        if os.is_none() {
-            if cfg!(target_os = "macos") {
-                (
-                    format!("avalanchego-macos-{}.zip", tag_name),
-                    DirDecoder::Zip,
-                )
-            } else if cfg!(unix) {
-                (
-                    format!("avalanchego-linux-{}-{}.tar.gz", arch, tag_name),
-                    DirDecoder::TarGzip,
-                )
-            } else if cfg!(windows) {
-                (
-                    format!("avalanchego-win-{}-experimental.zip", tag_name),
-                    DirDecoder::Zip,
-                )
-            } else {
-                (String::new(), DirDecoder::Zip)
-            }
-        } else {
-            let os = os.unwrap();
-            match os {
-                Os::MacOs => (
-                    format!("avalanchego-macos-{}.zip", tag_name),
-                    DirDecoder::Zip,
-                ),
-                Os::Linux => (
-                    format!("avalanchego-linux-{}-{}.tar.gz", arch, tag_name),
-                    DirDecoder::TarGzip,
-                ),
-                Os::Windows => (
-                    format!("avalanchego-win-{}-experimental.zip", tag_name),
-                    DirDecoder::Zip,
-                ),
-            }
+    // e.g., "{unpacked_dir}/build" or "{unpacked_dir}/avalanchego-v1.10.3"
+    let build_dir = outcome.binary_path.parent().unwrap().to_path_buf();
+    opts.cancel.check()?;
+    log::info!("installing {} to {}", build_dir.display(), target_dir);
+    crate::install::copy_dir_all(&build_dir, target_dir)?;
+    drop(workspace);
+
+    outcome.binary_path = Path::new(target_dir).join("avalanchego");
+    if opts.verify_version {
+        outcome = outcome.verify_version_blocking().await?;
+    }
+    InstallReceipt::record(&outcome)?;
+    lock::record_pin(opts.lock_file.as_deref(), &outcome)?;
+    outcome.elapsed = started.elapsed();
+    Ok(outcome)
+}
+
+/// Same as "install_with_options", and writes the node config to
+/// "{target_dir}/config.json" with "plugin-dir" pointing to the installed plugins.
+/// The config is validated before downloading.
+pub async fn install_with_config(
+    opts: &Options,
+    target_dir: &str,
+    config: &super::config::Config,
+) -> io::Result<InstallOutcome> {
+    let mut config = config.clone();
+    config.plugin_dir = super::get_plugin_dir(Path::new(target_dir).join("avalanchego"));
+    config.validate()?;
+
+    let outcome = install_with_options(opts, target_dir).await?;
+    config.sync(Path::new(target_dir).join(super::config::DEFAULT_CONFIG_FILE_NAME))?;
+    Ok(outcome)
+}
+
+/// Same as "install_with_options" but skips the download if the "target_dir"
+/// already has the "avalanchego" binary of the resolved release tag and platform.
+/// Re-installs if the installed binary is of a different release, corrupted,
+/// or not installed from the asset pinned in the lockfile.
+/// Returns the install outcome with "cache_hit" set if nothing was downloaded.
+pub async fn ensure_installed(opts: &Options, target_dir: &str) -> io::Result<InstallOutcome> {
+    crate::release::ensure_installed(
+        Project::AvalancheGo,
+        opts,
+        Path::new(target_dir).join("avalanchego"),
+        |opts| async move { install_with_options(&opts, target_dir).await },
+    )
+    .await
+}
+
+/// Downloads and unpacks the release archive to a new workspace.
+/// Returns the install outcome with the path to the unpacked binary,
+/// and the workspace, which removes the unpacked files when dropped.
+/// Set "target_dir" if the unpacked files are to be copied there,
+/// and leave it empty if the unpacked binary is to be run from the workspace.
+async fn download_and_unpack(
+    opts: &Options,
+    target_dir: Option<&Path>,
+) -> io::Result<(InstallOutcome, Workspace)> {
+    let started = Instant::now();
+    let platform = Platform::new_or_detect(opts.os, opts.arch)?;
+    platform.ensure_supported("avalanchego", SUPPORTED_PLATFORMS)?;
+
+    let pinned = lock::find_pin(
+        opts.lock_file.as_deref(),
+        Project::AvalancheGo,
+        platform,
+        opts.release_tag.as_deref(),
+    )?;
+    let (tag_name, source_url, asset_size, archive_kind) = match &pinned {
+        Some(pin) => (
+            pin.tag_name.clone(),
+            pin.source_url.clone(),
+            None,
+            ArchiveKind::from_file_name(&pin.source_url)?,
+        ),
+        None => {
+            let (tag_name, asset, archive_kind) = opts
+                .cancel
+                .run(resolve_asset(&platform, opts.release_tag.clone()))
+                .await?;
+            (
+                tag_name,
+                asset.browser_download_url,
+                asset.size,
+                archive_kind,
+            )
         }
     };
-    if file_name.is_empty() {
-        return Err(Error::new(
-            ErrorKind::Other,
-            format!("unknown platform '{}'", env::consts::OS),
-        ));
-    }
 
-    log::info!("downloading latest avalanchego '{}'", file_name);
-    let download_url = format!(
-        "https://github.com/ava-labs/avalanchego/releases/download/{}/{}",
-        tag_name, file_name
-    );
-    let tmp_file_path = random_manager::tmp_path(10, Some(dir_decoder.suffix()))?;
-    download_file(&download_url, &tmp_file_path).await?;
+    log::info!("downloading avalanchego {} from '{}'", tag_name, source_url);
+    let workspace = if target_dir.is_none() {
+        Workspace::new_executable(
+            opts.workspace_root.as_deref(),
+            opts.fallback_workspace_root.as_deref(),
+            opts.keep_workspace,
+        )?
+    } else {
+        Workspace::new(opts.workspace_root.as_deref(), opts.keep_workspace)?
+    };
+    let tmp_file_path = workspace.join_str(format!("avalanchego{}", archive_kind.suffix()))?;
+    let downloaded = download_archive(
+        opts,
+        &source_url,
+        &tmp_file_path,
+        asset_size,
+        workspace.path(),
+        target_dir,
+    )
+    .await?;
+    lock::check_pin(
+        pinned.as_ref(),
+        &Pin {
+            project: Project::AvalancheGo,
+            platform,
+            tag_name: tag_name.clone(),
+            source_url: source_url.clone(),
+            sha256: downloaded.sha256.clone(),
+        },
+    )?;
 
-    let dst_dir_path = random_manager::tmp_path(10, None)?;
+    let dst_dir_path = workspace.join_str("unpacked")?;
     log::info!("unpacking {} to {}", tmp_file_path, dst_dir_path);
-    compress_manager::unpack_directory(&tmp_file_path, &dst_dir_path, dir_decoder.clone())?;
+    extract::unpack_with_cancel(
+        &tmp_file_path,
+        &dst_dir_path,
+        archive_kind,
+        &opts.unpack_limits,
+        &opts.cancel,
+    )?;
 
     // TODO: this can fail due to files being still busy...
     log::info!("cleaning up downloaded file {}", tmp_file_path);
@@ -171,7 +246,7 @@ pub async fn download(
         ),
     }
 
-    let avalanchego_path = if dir_decoder.clone().suffix() == DirDecoder::Zip.suffix() {
+    let avalanchego_path = if archive_kind == ArchiveKind::Zip {
         Path::new(&dst_dir_path).join("build").join("avalanchego")
     } else {
         Path::new(&dst_dir_path)
@@ -183,91 +258,248 @@ pub async fn download(
         let f = File::open(&avalanchego_path)?;
         f.set_permissions(PermissionsExt::from_mode(0o777))?;
     }
-    Ok(String::from(avalanchego_path.as_os_str().to_str().unwrap()))
+    let outcome = InstallOutcome {
+        project: Project::AvalancheGo,
+        binary_path: avalanchego_path,
+        tag_name,
+        platform,
+        source_url,
+        archive_sha256: downloaded.sha256,
+        bytes_downloaded: downloaded.bytes,
+        cache_hit: false,
+        elapsed: started.elapsed(),
+        version: None,
+    };
+    Ok((outcome, workspace))
 }
 
-/// Represents the AvalancheGo release "arch".
-#[derive(Eq, PartialEq, Clone)]
-pub enum Arch {
-    Amd64,
-    Arm64,
+/// Resolves the "release_tag", or the latest release if none,
+/// and selects its "avalanchego" release asset for the platform.
+/// Returns the resolved tag, the asset, and the decoder for its archive type.
+pub async fn resolve_asset(
+    platform: &Platform,
+    release_tag: Option<String>,
+) -> io::Result<(String, crate::github::Asset, ArchiveKind)> {
+    platform.ensure_supported("avalanchego", SUPPORTED_PLATFORMS)?;
+    let release_info =
+        crate::release::fetch_release_info(Project::AvalancheGo, release_tag).await?;
+    // e.g., "v1.10.0"
+    let tag_name = release_info.tag_name.clone().unwrap();
+    let (asset, archive_kind) = select_asset(
+        release_info.assets.as_deref().unwrap_or_default(),
+        platform,
+        &tag_name,
+    )?;
+    Ok((tag_name, asset, archive_kind))
 }
 
-/// ref. https://doc.rust-lang.org/std/string/trait.ToString.html
-/// ref. https://doc.rust-lang.org/std/fmt/trait.Display.html
-/// Use "Self.to_string()" to directly invoke this
-impl fmt::Display for Arch {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            Arch::Amd64 => write!(f, "amd64"),
-            Arch::Arm64 => write!(f, "arm64"),
-        }
+/// Selects the "avalanchego" release asset for the platform,
+/// from the list of assets published for the "tag_name".
+/// Returns the asset with the decoder for its archive type.
+///
+/// The rules are:
+/// - "linux" requires the arch-specific build
+///   (e.g., "avalanchego-linux-arm64-v1.10.3.tar.gz").
+/// - "macos" prefers the arch-specific build if the release publishes one
+///   (e.g., "avalanchego-macos-arm64-v1.10.4.zip"), and otherwise falls back to
+///   the single macOS build (e.g., "avalanchego-macos-v1.10.3.zip"),
+///   which runs on Apple Silicon under Rosetta 2.
+/// - "win" only has the amd64 build
+///   (e.g., "avalanchego-win-v1.10.3-experimental.zip").
+///
+/// The fallbacks depend on which assets the release publishes,
+/// not on the tag range, so new releases need no code changes.
+/// Only the assets whose names have the "tag_name" as a "-" or "_" separated
+/// token are considered (e.g., "v1.10.3" does not match "v1.10.30").
+///
+/// ref. <https://github.com/ava-labs/avalanchego/releases>
+pub fn select_asset(
+    assets: &[crate::github::Asset],
+    platform: &Platform,
+    tag_name: &str,
+) -> io::Result<(crate::github::Asset, ArchiveKind)> {
+    platform.ensure_supported("avalanchego", SUPPORTED_PLATFORMS)?;
+
+    // only consider the assets for the requested tag
+    let tagged: Vec<crate::github::Asset> = assets
+        .iter()
+        .filter(|a| a.has_any_token(&[tag_name]))
+        .cloned()
+        .collect();
+    if tagged.is_empty() {
+        return Err(Error::new(
+            ErrorKind::NotFound,
+            format!(
+                "no avalanchego release asset for tag {}; available assets: {:?}",
+                tag_name,
+                assets.iter().map(|a| &a.name).collect::<Vec<_>>()
+            ),
+        ));
     }
-}
+    let release_info = crate::github::ReleaseResponse {
+        tag_name: Some(tag_name.to_string()),
+        assets: Some(tagged),
+        ..Default::default()
+    };
+    let (asset, archive_kind) = release_info.select_asset(&crate::github::AssetQuery {
+        prefix: "avalanchego",
+        os_tokens: platform.os.asset_tokens(),
+        arch_tokens: platform.arch.asset_tokens(),
+        known_arch_tokens: crate::platform::ARCH_ASSET_TOKENS,
+        allow_arch_agnostic: platform.os != Os::Linux,
+    })?;
 
-impl Arch {
-    pub fn new(arch: &str) -> io::Result<Self> {
-        match arch {
-            "amd64" => Ok(Arch::Amd64),
-            "arm64" => Ok(Arch::Arm64),
-            _ => Err(Error::new(
-                ErrorKind::InvalidInput,
-                format!("unknown arch {}", arch),
-            )),
-        }
+    if platform.os == Os::MacOs
+        && platform.arch == Arch::Arm64
+        && !asset.has_any_token(platform.arch.asset_tokens())
+    {
+        log::warn!(
+            "no native {platform} avalanchego build for {tag_name}, falling back to '{}' (requires Rosetta 2)",
+            asset.name
+        );
     }
+    Ok((asset, archive_kind))
 }
 
-/// Represents the AvalancheGo release "os".
-#[derive(Eq, PartialEq, Clone)]
-pub enum Os {
-    MacOs,
-    Linux,
-    Windows,
-}
+/// RUST_LOG=debug cargo test --package avalanche-installer --lib -- avalanchego::github::test_select_asset --exact --show-output
+#[test]
+fn test_select_asset() {
+    let _ = env_logger::builder()
+        .filter_level(log::LevelFilter::Info)
+        .is_test(true)
+        .try_init();
 
-/// ref. https://doc.rust-lang.org/std/string/trait.ToString.html
-/// ref. https://doc.rust-lang.org/std/fmt/trait.Display.html
-/// Use "Self.to_string()" to directly invoke this
-impl fmt::Display for Os {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            Os::MacOs => write!(f, "macos"),
-            Os::Linux => write!(f, "linux"),
-            Os::Windows => write!(f, "win"),
+    let new_test_assets = |names: &[&str]| -> Vec<crate::github::Asset> {
+        names
+            .iter()
+            .map(|name| crate::github::Asset {
+                name: name.to_string(),
+                browser_download_url: format!(
+                    "https://github.com/ava-labs/avalanchego/releases/download/test/{name}"
+                ),
+                size: None,
+            })
+            .collect()
+    };
+
+    // single macOS build, as published up to v1.10.3
+    let assets = new_test_assets(&[
+        "avalanchego-linux-amd64-v1.10.3.tar.gz",
+        "avalanchego-linux-amd64-v1.10.3.tar.gz.sig",
+        "avalanchego-linux-arm64-v1.10.3.tar.gz",
+        "avalanchego-linux-arm64-v1.10.3.tar.gz.sig",
+        "avalanchego-macos-v1.10.3.zip",
+        "avalanchego-win-v1.10.3-experimental.zip",
+    ]);
+    for (os, arch, expected) in [
+        (
+            Os::Linux,
+            Arch::Amd64,
+            Some("avalanchego-linux-amd64-v1.10.3.tar.gz"),
+        ),
+        (
+            Os::Linux,
+            Arch::Arm64,
+            Some("avalanchego-linux-arm64-v1.10.3.tar.gz"),
+        ),
+        (
+            Os::MacOs,
+            Arch::Amd64,
+            Some("avalanchego-macos-v1.10.3.zip"),
+        ),
+        (
+            Os::MacOs,
+            Arch::Arm64,
+            Some("avalanchego-macos-v1.10.3.zip"),
+        ),
+        (
+            Os::Windows,
+            Arch::Amd64,
+            Some("avalanchego-win-v1.10.3-experimental.zip"),
+        ),
+        (Os::Windows, Arch::Arm64, None),
+    ] {
+        let selected = select_asset(&assets, &Platform::new(os, arch), "v1.10.3");
+        match expected {
+            Some(name) => assert_eq!(selected.unwrap().0.name, name, "{os}/{arch}"),
+            None => assert!(selected.is_err(), "{os}/{arch}"),
         }
     }
-}
 
-impl Os {
-    pub fn new(os: &str) -> io::Result<Self> {
-        match os {
-            "macos" => Ok(Os::MacOs),
-            "linux" => Ok(Os::Linux),
-            "win" => Ok(Os::Windows),
-            _ => Err(Error::new(
-                ErrorKind::InvalidInput,
-                format!("unknown os {}", os),
-            )),
+    // arch-specific macOS builds
+    let assets = new_test_assets(&[
+        "avalanchego-linux-amd64-v1.10.4.tar.gz",
+        "avalanchego-linux-arm64-v1.10.4.tar.gz",
+        "avalanchego-macos-amd64-v1.10.4.zip",
+        "avalanchego-macos-arm64-v1.10.4.zip",
+        "avalanchego-win-v1.10.4-experimental.zip",
+    ]);
+    for (os, arch, expected) in [
+        (
+            Os::Linux,
+            Arch::Amd64,
+            Some("avalanchego-linux-amd64-v1.10.4.tar.gz"),
+        ),
+        (
+            Os::Linux,
+            Arch::Arm64,
+            Some("avalanchego-linux-arm64-v1.10.4.tar.gz"),
+        ),
+        (
+            Os::MacOs,
+            Arch::Amd64,
+            Some("avalanchego-macos-amd64-v1.10.4.zip"),
+        ),
+        (
+            Os::MacOs,
+            Arch::Arm64,
+            Some("avalanchego-macos-arm64-v1.10.4.zip"),
+        ),
+        (
+            Os::Windows,
+            Arch::Amd64,
+            Some("avalanchego-win-v1.10.4-experimental.zip"),
+        ),
+        (Os::Windows, Arch::Arm64, None),
+    ] {
+        let selected = select_asset(&assets, &Platform::new(os, arch), "v1.10.4");
+        match expected {
+            Some(name) => assert_eq!(selected.unwrap().0.name, name, "{os}/{arch}"),
+            None => assert!(selected.is_err(), "{os}/{arch}"),
         }
     }
-}
 
-/// Downloads a file to the "file_path".
-pub async fn download_file(ep: &str, file_path: &str) -> io::Result<()> {
-    log::info!("downloading the file via {}", ep);
-    let resp = reqwest::get(ep)
-        .await
-        .map_err(|e| Error::new(ErrorKind::Other, format!("failed reqwest::get {}", e)))?;
-
-    let mut content = Cursor::new(
-        resp.bytes()
-            .await
-            .map_err(|e| Error::new(ErrorKind::Other, format!("failed bytes {}", e)))?,
-    );
+    // missing linux arm64 build must not fall back to another arch
+    let assets = new_test_assets(&[
+        "avalanchego-linux-amd64-v1.7.0.tar.gz",
+        "avalanchego-macos-v1.7.0.zip",
+    ]);
+    match select_asset(&assets, &Platform::new(Os::Linux, Arch::Arm64), "v1.7.0") {
+        Ok((asset, _)) => panic!("unexpected asset {}", asset.name),
+        Err(e) => assert!(e
+            .to_string()
+            .contains("avalanchego-linux-amd64-v1.7.0.tar.gz")),
+    }
 
-    let mut f = File::create(file_path)?;
-    copy(&mut content, &mut f)?;
+    // assets for other tags are never selected
+    assert!(select_asset(&assets, &Platform::new(Os::Linux, Arch::Amd64), "v1.10.3").is_err());
 
-    Ok(())
+    // the tag is matched as a whole token, not as a substring
+    let assets = new_test_assets(&[
+        "avalanchego-linux-amd64-v1.10.30.tar.gz",
+        "avalanchego-linux-amd64-v1.10.3.tar.gz",
+    ]);
+    let (asset, _) =
+        select_asset(&assets, &Platform::new(Os::Linux, Arch::Amd64), "v1.10.3").unwrap();
+    assert_eq!(asset.name, "avalanchego-linux-amd64-v1.10.3.tar.gz");
+    let err = select_asset(
+        &assets[..1],
+        &Platform::new(Os::Linux, Arch::Amd64),
+        "v1.10.3",
+    )
+    .err()
+    .unwrap();
+    assert_eq!(err.kind(), ErrorKind::NotFound);
+    // lists the assets once
+    assert_eq!(err.to_string().matches("v1.10.30").count(), 1, "{}", err);
 }
@@ -1,4 +1,6 @@
+pub mod config;
 pub mod github;
+pub mod systemd;
 
 use std::path::Path;
 
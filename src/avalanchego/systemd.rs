@@ -0,0 +1,232 @@
+use std::{
+    io::{self, Error, ErrorKind},
+    path::{Path, PathBuf},
+};
+
+use crate::install::write_file_atomic;
+
+/// Default path to install the "avalanchego" systemd unit.
+pub const DEFAULT_UNIT_PATH: &str = "/etc/systemd/system/avalanchego.service";
+
+/// Represents the hardened systemd unit to run the installed "avalanchego".
+/// ref. <https://www.freedesktop.org/software/systemd/man/systemd.exec.html>
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Unit {
+    /// Path to the installed "avalanchego" binary.
+    pub binary_path: PathBuf,
+    /// Path to the node config file, passed as "--config-file".
+    pub config_file: PathBuf,
+    /// The only directory the service is allowed to write to.
+    pub data_dir: PathBuf,
+    /// User to run the service as.
+    pub user: String,
+    /// Leave empty to use the primary group of the user.
+    pub group: Option<String>,
+    pub limit_nofile: u64,
+    pub restart_sec: u64,
+}
+
+impl Unit {
+    pub fn new<P: AsRef<Path>, Q: AsRef<Path>, R: AsRef<Path>>(
+        binary_path: P,
+        config_file: Q,
+        data_dir: R,
+        user: &str,
+    ) -> Self {
+        Self {
+            binary_path: binary_path.as_ref().to_path_buf(),
+            config_file: config_file.as_ref().to_path_buf(),
+            data_dir: data_dir.as_ref().to_path_buf(),
+            user: user.to_string(),
+            group: None,
+            limit_nofile: 65536,
+            restart_sec: 5,
+        }
+    }
+
+    /// Returns an error if a path is relative or has characters that
+    /// systemd would split on or expand (e.g., whitespace, quotes, "%", "$"),
+    /// since the paths are rendered unquoted.
+    /// ref. <https://www.freedesktop.org/software/systemd/man/systemd.service.html#Command%20lines>
+    pub fn validate(&self) -> io::Result<()> {
+        for (name, path) in [
+            ("binary_path", &self.binary_path),
+            ("config_file", &self.config_file),
+            ("data_dir", &self.data_dir),
+        ] {
+            let valid = path.is_absolute()
+                && path.to_str().is_some_and(|v| {
+                    !v.chars().any(|c| {
+                        c.is_whitespace()
+                            || c.is_control()
+                            || matches!(c, '"' | '\'' | '\\' | '%' | '$' | ';')
+                    })
+                });
+            if !valid {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!(
+                        "{} '{}' must be an absolute path without whitespace, quotes, '%', '$' or ';'",
+                        name,
+                        path.display()
+                    ),
+                ));
+            }
+        }
+        for (name, v) in [("user", Some(&self.user)), ("group", self.group.as_ref())] {
+            if let Some(v) = v {
+                if v.is_empty() || v.chars().any(|c| c.is_whitespace() || c.is_control()) {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("invalid {} '{}'", name, v),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Renders the unit file. Call "validate" first, since the paths
+    /// are rendered as they are.
+    pub fn render(&self) -> String {
+        let group = match &self.group {
+            Some(v) => format!("Group={}\n", v),
+            None => String::new(),
+        };
+        format!(
+            "[Unit]
+Description=Avalanche node (avalanchego)
+Documentation=https://docs.avax.network
+After=network-online.target
+Wants=network-online.target
+
+[Service]
+Type=simple
+User={user}
+{group}ExecStart={binary_path} --config-file={config_file}
+WorkingDirectory={data_dir}
+Restart=always
+RestartSec={restart_sec}
+LimitNOFILE={limit_nofile}
+NoNewPrivileges=true
+ProtectSystem=strict
+ProtectHome=read-only
+ReadWritePaths={data_dir}
+PrivateTmp=true
+PrivateDevices=true
+ProtectKernelTunables=true
+ProtectKernelModules=true
+ProtectKernelLogs=true
+ProtectControlGroups=true
+RestrictSUIDSGID=true
+RestrictRealtime=true
+LockPersonality=true
+
+[Install]
+WantedBy=multi-user.target
+",
+            user = self.user,
+            group = group,
+            binary_path = self.binary_path.display(),
+            config_file = self.config_file.display(),
+            data_dir = self.data_dir.display(),
+            restart_sec = self.restart_sec,
+            limit_nofile = self.limit_nofile,
+        )
+    }
+
+    /// Renders the unit file and writes it to the "unit_path",
+    /// unless "dry_run" is set. Returns the rendered unit for review.
+    /// Run "systemctl daemon-reload" after writing.
+    pub fn write<P: AsRef<Path>>(&self, unit_path: P, dry_run: bool) -> io::Result<String> {
+        let unit_path = unit_path.as_ref();
+        self.validate()?;
+        let rendered = self.render();
+        if dry_run {
+            log::info!("dry-run, skipping writing {}", unit_path.display());
+            return Ok(rendered);
+        }
+
+        log::info!("writing systemd unit to {}", unit_path.display());
+        write_file_atomic(unit_path, rendered.as_bytes())?;
+        Ok(rendered)
+    }
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-installer --lib -- avalanchego::systemd::test_render --exact --show-output
+#[test]
+fn test_render() {
+    let mut unit = Unit::new(
+        "/opt/avalanche/current/avalanchego",
+        "/etc/avalanchego/config.json",
+        "/var/lib/avalanchego",
+        "avalanche",
+    );
+    assert_eq!(
+        unit.render(),
+        "[Unit]
+Description=Avalanche node (avalanchego)
+Documentation=https://docs.avax.network
+After=network-online.target
+Wants=network-online.target
+
+[Service]
+Type=simple
+User=avalanche
+ExecStart=/opt/avalanche/current/avalanchego --config-file=/etc/avalanchego/config.json
+WorkingDirectory=/var/lib/avalanchego
+Restart=always
+RestartSec=5
+LimitNOFILE=65536
+NoNewPrivileges=true
+ProtectSystem=strict
+ProtectHome=read-only
+ReadWritePaths=/var/lib/avalanchego
+PrivateTmp=true
+PrivateDevices=true
+ProtectKernelTunables=true
+ProtectKernelModules=true
+ProtectKernelLogs=true
+ProtectControlGroups=true
+RestrictSUIDSGID=true
+RestrictRealtime=true
+LockPersonality=true
+
+[Install]
+WantedBy=multi-user.target
+"
+    );
+
+    unit.group = Some("avalanche".to_string());
+    let rendered = unit.render();
+    assert!(rendered.contains("User=avalanche\nGroup=avalanche\nExecStart="));
+
+    for (binary_path, data_dir) in [
+        ("/opt/my avalanche/avalanchego", "/var/lib/avalanchego"),
+        (
+            "/opt/avalanche/avalanchego",
+            "/var/lib/avalanchego\nExecStartPre=/bin/sh",
+        ),
+        ("/opt/avalanche/avalanchego", "/var/lib/%h"),
+        ("/opt/avalanche/avalanchego", "/var/lib/$HOME"),
+        ("avalanchego", "/var/lib/avalanchego"),
+    ] {
+        let mut evil = unit.clone();
+        evil.binary_path = PathBuf::from(binary_path);
+        evil.data_dir = PathBuf::from(data_dir);
+        let err = evil
+            .write("/nonexistent/avalanchego.service", true)
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    }
+    let mut evil = unit.clone();
+    evil.user = "avalanche\nUser=root".to_string();
+    assert!(evil.validate().is_err());
+
+    let unit_path = random_manager::tmp_path(10, Some(".service")).unwrap();
+    assert_eq!(unit.write(&unit_path, true).unwrap(), rendered);
+    assert!(!Path::new(&unit_path).exists());
+    assert_eq!(unit.write(&unit_path, false).unwrap(), rendered);
+    assert_eq!(std::fs::read_to_string(&unit_path).unwrap(), rendered);
+    std::fs::remove_file(&unit_path).unwrap();
+}
@@ -0,0 +1,278 @@
+use std::{
+    io::{self, Error, ErrorKind},
+    sync::Arc,
+    time::Duration,
+};
+
+use tokio::{sync::Semaphore, task::JoinSet};
+
+use crate::{
+    download,
+    install::{InstallOutcome, Options},
+    project::Project,
+};
+
+/// Represents a binary to install in a batch.
+#[derive(Debug, Clone)]
+pub struct Target {
+    pub project: Project,
+    /// Directory to install "avalanchego" into,
+    /// or the file path to install the plugin binary as
+    /// (e.g., "{plugin_dir}/{vm_id}").
+    pub target_path: String,
+    pub opts: Options,
+}
+
+impl Target {
+    pub fn new(project: Project, target_path: &str, opts: Options) -> Self {
+        Self {
+            project,
+            target_path: target_path.to_string(),
+            opts,
+        }
+    }
+
+    /// Installs the target, skipping the download if already installed.
+    pub async fn install(&self) -> io::Result<InstallOutcome> {
+        match self.project {
+            #[cfg(feature = "avalanchego")]
+            Project::AvalancheGo => {
+                crate::avalanchego::github::ensure_installed(&self.opts, &self.target_path).await
+            }
+            #[cfg(feature = "subnet_evm")]
+            Project::SubnetEvm => {
+                crate::subnet_evm::github::ensure_installed(&self.opts, &self.target_path).await
+            }
+            #[allow(unreachable_patterns)]
+            project => Err(project.not_enabled()),
+        }
+    }
+}
+
+/// Represents the options to install a batch of targets.
+#[derive(Debug, Clone)]
+pub struct BatchOptions {
+    /// Maximum number of targets to install at the same time.
+    pub parallelism: usize,
+    /// Set true to abort the remaining targets on the first failure.
+    pub fail_fast: bool,
+}
+
+impl Default for BatchOptions {
+    fn default() -> Self {
+        Self {
+            parallelism: 4,
+            fail_fast: false,
+        }
+    }
+}
+
+/// Represents the install result of a target in the batch.
+#[derive(Debug)]
+pub struct TargetResult {
+    pub target: Target,
+    pub result: io::Result<InstallOutcome>,
+}
+
+/// Installs the targets concurrently, up to "parallelism" at a time,
+/// sharing the HTTP clients for the downloads unless a target sets its own.
+/// Targets with the same "connect" timeout share one client, built with it.
+/// Returns the results in the order of the targets. A failed target does not
+/// stop the others, unless "fail_fast" is set, in which case the targets
+/// not finished yet fail with "ErrorKind::Interrupted".
+pub async fn install_all(
+    targets: Vec<Target>,
+    batch_opts: &BatchOptions,
+) -> io::Result<Vec<TargetResult>> {
+    let mut clients: Vec<(Duration, reqwest::Client)> = Vec::new();
+    let semaphore = Arc::new(Semaphore::new(batch_opts.parallelism.max(1)));
+    log::info!(
+        "installing {} targets with parallelism {}",
+        targets.len(),
+        batch_opts.parallelism.max(1)
+    );
+
+    let mut join_set = JoinSet::new();
+    for (idx, target) in targets.iter().enumerate() {
+        let mut target = target.clone();
+        if target.opts.client.is_none() {
+            let connect = target.opts.timeouts.connect;
+            let cli = match clients.iter().find(|(d, _)| *d == connect) {
+                Some((_, cli)) => cli.clone(),
+                None => {
+                    let cli = download::new_client_with_timeouts(&target.opts.timeouts)?;
+                    clients.push((connect, cli.clone()));
+                    cli
+                }
+            };
+            target.opts.client = Some(cli);
+        }
+        let semaphore = semaphore.clone();
+        join_set.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            log::info!("installing {} to {}", target.project, target.target_path);
+            (idx, target.install().await)
+        });
+    }
+
+    let mut results: Vec<Option<io::Result<InstallOutcome>>> =
+        targets.iter().map(|_| None).collect();
+    while let Some(joined) = join_set.join_next().await {
+        let (idx, result) = match joined {
+            Ok(v) => v,
+            Err(e) if e.is_cancelled() => continue,
+            Err(e) => {
+                return Err(Error::new(
+                    ErrorKind::Other,
+                    format!("failed to join install task {}", e),
+                ))
+            }
+        };
+        if let Err(e) = &result {
+            log::warn!(
+                "failed to install {} to {} ({})",
+                targets[idx].project,
+                targets[idx].target_path,
+                e
+            );
+            if batch_opts.fail_fast {
+                join_set.abort_all();
+            }
+        }
+        results[idx] = Some(result);
+    }
+
+    Ok(targets
+        .into_iter()
+        .zip(results)
+        .map(|(target, result)| TargetResult {
+            result: result.unwrap_or_else(|| {
+                Err(Error::new(
+                    ErrorKind::Interrupted,
+                    format!(
+                        "aborted installing {} to {} after another target failed",
+                        target.project, target.target_path
+                    ),
+                ))
+            }),
+            target,
+        })
+        .collect())
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-installer --lib -- batch::test_install_all --exact --show-output
+#[cfg(feature = "subnet_evm")]
+#[tokio::test]
+async fn test_install_all() {
+    use crate::{
+        install::FakeRelease,
+        platform::{Arch, Os, Platform},
+    };
+    use std::{fs, path::Path, sync::atomic::Ordering};
+
+    let _ = env_logger::builder()
+        .filter_level(log::LevelFilter::Info)
+        .is_test(true)
+        .try_init();
+
+    let root_dir = Path::new(&random_manager::tmp_path(10, None).unwrap()).to_path_buf();
+    fs::create_dir_all(&root_dir).unwrap();
+
+    // pinned, so that nothing is fetched from GitHub
+    let platform = Platform::new(Os::Linux, Arch::Amd64);
+    let serve = |name: &str, delay: Duration| {
+        let lock_file = root_dir.join(name);
+        let release = FakeRelease {
+            delay,
+            ..FakeRelease::new("v0.5.1")
+        };
+        let max_in_flight = release.serve(&lock_file, platform);
+        (lock_file, max_in_flight)
+    };
+    let target = |vm_id: &str, lock_file: &Path| {
+        Target::new(
+            Project::SubnetEvm,
+            &root_dir.join(vm_id).to_string_lossy(),
+            Options {
+                os: Some(platform.os),
+                arch: Some(platform.arch),
+                lock_file: Some(lock_file.to_path_buf()),
+                workspace_root: Some(root_dir.clone()),
+                ..Default::default()
+            },
+        )
+    };
+    // subnet-evm does not support windows, so fails before downloading
+    let unsupported = || {
+        Target::new(
+            Project::SubnetEvm,
+            &root_dir.join("unsupported").to_string_lossy(),
+            Options {
+                os: Some(Os::Windows),
+                arch: Some(Arch::Amd64),
+                release_tag: Some(String::from("v0.5.1")),
+                ..Default::default()
+            },
+        )
+    };
+
+    let (lock, max_in_flight) = serve("parallel.lock", Duration::from_millis(300));
+    let targets = vec![
+        target("vm1", &lock),
+        target("vm2", &lock),
+        unsupported(),
+        target("vm3", &lock),
+        target("vm4", &lock),
+    ];
+    let results = install_all(
+        targets,
+        &BatchOptions {
+            parallelism: 2,
+            fail_fast: false,
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(results.len(), 5);
+    for idx in [0, 1, 3, 4] {
+        let outcome = results[idx].result.as_ref().unwrap();
+        assert!(!outcome.cache_hit);
+        assert!(outcome.binary_path.exists());
+    }
+    assert_eq!(
+        results[2].result.as_ref().unwrap_err().kind(),
+        ErrorKind::Unsupported
+    );
+    assert!(results[3].target.target_path.ends_with("vm3"));
+    assert_eq!(max_in_flight.load(Ordering::SeqCst), 2);
+
+    // the stalled downloads are aborted once the unsupported target fails
+    let (lock, _) = serve("fail-fast.lock", Duration::from_secs(60));
+    let targets = vec![target("vm5", &lock), unsupported(), target("vm6", &lock)];
+    let results = tokio::time::timeout(
+        Duration::from_secs(10),
+        install_all(
+            targets,
+            &BatchOptions {
+                parallelism: 4,
+                fail_fast: true,
+            },
+        ),
+    )
+    .await
+    .unwrap()
+    .unwrap();
+    assert_eq!(
+        results[1].result.as_ref().unwrap_err().kind(),
+        ErrorKind::Unsupported
+    );
+    for idx in [0, 2] {
+        assert_eq!(
+            results[idx].result.as_ref().unwrap_err().kind(),
+            ErrorKind::Interrupted
+        );
+    }
+    assert!(!root_dir.join("vm5").exists());
+
+    fs::remove_dir_all(&root_dir).unwrap();
+}
@@ -0,0 +1,663 @@
+use std::{
+    fs,
+    io::{self, Error, ErrorKind},
+    path::{Path, PathBuf},
+};
+
+use avalanche_installer::{
+    avalanchego, github,
+    install::{self, InstallReceipt, Verification},
+    lock,
+    network::Network,
+    platform::{Arch, Os, Platform},
+    project::Project,
+    store::{self, Store},
+    subnet_evm, update,
+};
+use clap::{crate_version, value_parser, Arg, ArgAction, ArgMatches, Command};
+use semver::VersionReq;
+use serde::Serialize;
+
+pub const APP_NAME: &str = "avalanche-installer";
+
+/// Default root directory of the versioned install layout. See "store::Store".
+pub const DEFAULT_ROOT_DIR: &str = "/opt/avalanche";
+
+fn command() -> Command {
+    Command::new(APP_NAME)
+        .version(crate_version!())
+        .about("Installs and manages avalanchego and its VM plugins")
+        .subcommand_required(true)
+        .arg_required_else_help(true)
+        .arg(
+            Arg::new("ROOT_DIR")
+                .long("root-dir")
+                .help("Root directory of the installed versions")
+                .global(true)
+                .default_value(DEFAULT_ROOT_DIR),
+        )
+        .arg(
+            Arg::new("NETWORK")
+                .long("network")
+                .help("Network to manage the installs of (e.g., mainnet, fuji, local, 1337)")
+                .global(true)
+                .value_parser(|s: &str| Network::new(s))
+                .default_value("mainnet"),
+        )
+        .arg(
+            Arg::new("LOCK_FILE")
+                .long("lock-file")
+                .help("Lockfile to pin the installed release assets in (e.g., avalanche-installer.lock)")
+                .global(true),
+        )
+        .arg(
+            Arg::new("WORKSPACE_ROOT")
+                .long("workspace-root")
+                .help("Directory for the temporary download and unpack files (default: system temp dir)")
+                .global(true),
+        )
+        .arg(
+            Arg::new("KEEP_WORKSPACE")
+                .long("keep-workspace")
+                .help("Keeps the temporary download and unpack files for debugging")
+                .global(true)
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("JSON")
+                .long("json")
+                .help("Prints the output in JSON")
+                .global(true)
+                .action(ArgAction::SetTrue),
+        )
+        .subcommand(
+            Command::new("install")
+                .about("Installs avalanchego or a VM plugin")
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("avalanchego")
+                        .about("Installs avalanchego, and activates it")
+                        .arg(version_arg())
+                        .arg(os_arg())
+                        .arg(arch_arg())
+                        .arg(
+                            Arg::new("NO_ACTIVATE")
+                                .long("no-activate")
+                                .help("Skips activating the installed version")
+                                .action(ArgAction::SetTrue),
+                        ),
+                )
+                .subcommand(
+                    Command::new("plugin")
+                        .about("Installs a VM plugin for an installed avalanchego")
+                        .arg(
+                            Arg::new("PROJECT")
+                                .help("Plugin project (e.g., subnet-evm)")
+                                .required(true)
+                                .value_parser(|s: &str| Project::new(s)),
+                        )
+                        .arg(
+                            Arg::new("VM_ID")
+                                .long("vm-id")
+                                .help("VM ID to install the plugin as")
+                                .required(true),
+                        )
+                        .arg(version_arg())
+                        .arg(
+                            Arg::new("AVALANCHEGO_VERSION")
+                                .long("avalanchego-version")
+                                .help("Installed avalanchego version to add the plugin to (default: active)"),
+                        )
+                        .arg(os_arg())
+                        .arg(arch_arg()),
+                ),
+        )
+        .subcommand(
+            Command::new("list-releases")
+                .about("Lists the published releases")
+                .arg(
+                    Arg::new("PROJECT")
+                        .help("Project to list the releases of")
+                        .value_parser(|s: &str| Project::new(s))
+                        .default_value("avalanchego"),
+                )
+                .arg(
+                    Arg::new("PRERELEASE")
+                        .long("prerelease")
+                        .help("Includes prereleases")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("LIMIT")
+                        .long("limit")
+                        .help("Maximum number of releases to list")
+                        .value_parser(value_parser!(usize))
+                        .default_value("10"),
+                ),
+        )
+        .subcommand(
+            Command::new("update-lock")
+                .about("Moves the pin in the lockfile forward to a new release")
+                .arg(
+                    Arg::new("PROJECT")
+                        .help("Project to update the pin of")
+                        .value_parser(|s: &str| Project::new(s))
+                        .default_value("avalanchego"),
+                )
+                .arg(version_arg())
+                .arg(os_arg())
+                .arg(arch_arg()),
+        )
+        .subcommand(Command::new("list-installed").about("Lists the installed versions"))
+        .subcommand(
+            Command::new("use")
+                .about("Activates an installed avalanchego version")
+                .arg(installed_version_arg().required(true)),
+        )
+        .subcommand(
+            Command::new("uninstall")
+                .about("Removes an installed avalanchego version, with its plugins")
+                .arg(installed_version_arg().required(true)),
+        )
+        .subcommand(
+            Command::new("verify")
+                .about("Verifies the installed binaries against their install records")
+                .arg(installed_version_arg().help("Installed version to verify (default: active)")),
+        )
+}
+
+/// Installed version, parsed into its release tag (e.g., "1.10.3" to "v1.10.3").
+fn installed_version_arg() -> Arg {
+    Arg::new("VERSION").value_parser(|s: &str| parse_installed_tag(s))
+}
+
+fn version_arg() -> Arg {
+    Arg::new("VERSION")
+        .long("version")
+        .help("Exact version (e.g., v1.10.3, 1.10.3) or version requirement (e.g., ^1.10), default latest")
+}
+
+fn os_arg() -> Arg {
+    Arg::new("OS")
+        .long("os")
+        .help("Target OS (default: local system)")
+        .value_parser(|s: &str| Os::new(s))
+}
+
+fn arch_arg() -> Arg {
+    Arg::new("ARCH")
+        .long("arch")
+        .help("Target architecture (default: local system)")
+        .value_parser(|s: &str| Arch::new(s))
+}
+
+/// cargo run --bin avalanche-installer -- --help
+#[tokio::main]
+async fn main() -> io::Result<()> {
+    // ref. https://github.com/env-logger-rs/env_logger/issues/47
+    env_logger::init_from_env(
+        env_logger::Env::default().filter_or(env_logger::DEFAULT_FILTER_ENV, "info"),
+    );
+
+    let matches = command().get_matches();
+    let root_dir = matches.get_one::<String>("ROOT_DIR").unwrap();
+    let network = *matches.get_one::<Network>("NETWORK").unwrap();
+    let store = Store::new_for_network(root_dir, network);
+    let json = matches.get_flag("JSON");
+    let lock_file = matches.get_one::<String>("LOCK_FILE").map(PathBuf::from);
+    let lock_file = lock_file.as_deref();
+
+    match matches.subcommand() {
+        Some(("install", sub)) => match sub.subcommand() {
+            Some(("avalanchego", sub)) => install_avalanchego(&store, sub, lock_file, json).await,
+            Some(("plugin", sub)) => install_plugin(&store, sub, lock_file, json).await,
+            _ => unreachable!("unknown install subcommand"),
+        },
+        Some(("list-releases", sub)) => list_releases(sub, json).await,
+        Some(("update-lock", sub)) => update_lock(sub, lock_file, json).await,
+        Some(("list-installed", _)) => list_installed(&store, json),
+        Some(("use", sub)) => {
+            let tag_name = sub.get_one::<String>("VERSION").unwrap();
+            store.activate(tag_name)?;
+            print(json, tag_name, || format!("activated {}", tag_name))
+        }
+        Some(("uninstall", sub)) => {
+            let tag_name = sub.get_one::<String>("VERSION").unwrap();
+            store.remove(tag_name)?;
+            print(json, tag_name, || format!("uninstalled {}", tag_name))
+        }
+        Some(("verify", sub)) => verify(&store, sub.get_one::<String>("VERSION"), json),
+        _ => unreachable!("unknown subcommand"),
+    }
+}
+
+/// Prints the value in JSON if "json" is set, otherwise the text.
+fn print<T: Serialize, F: FnOnce() -> String>(json: bool, v: &T, text: F) -> io::Result<()> {
+    if json {
+        let s = serde_json::to_string_pretty(v).map_err(|e| {
+            Error::new(
+                ErrorKind::Other,
+                format!("failed to serialize output {}", e),
+            )
+        })?;
+        println!("{}", s);
+    } else {
+        println!("{}", text());
+    }
+    Ok(())
+}
+
+/// Adds the "v" prefix to the version if missing (e.g., "1.10.3" to "v1.10.3").
+fn to_tag(version: &str) -> String {
+    if version.starts_with('v') {
+        version.to_string()
+    } else {
+        format!("v{}", version)
+    }
+}
+
+/// Parses the installed version into its release tag.
+/// Returns an error if the tag is not a plain directory name in the store
+/// (e.g., "v1/../.."), so nothing outside the versions directory is touched.
+fn parse_installed_tag(version: &str) -> io::Result<String> {
+    let tag_name = to_tag(version);
+    store::validate_tag(&tag_name)?;
+    Ok(tag_name)
+}
+
+/// Represents the "--version" argument.
+#[derive(Debug, PartialEq)]
+enum VersionArg {
+    /// Release tag (e.g., "v1.10.3").
+    Tag(String),
+    /// Version requirement (e.g., "^1.10", "~1.10.3").
+    Req(VersionReq),
+}
+
+impl VersionArg {
+    /// Parses the full version, with or without the "v" prefix
+    /// (e.g., "v1.10.3", "1.10.3"), as the exact release tag,
+    /// and anything else as the version requirement (e.g., "^1.10", ">=1.10.3").
+    fn parse(version: &str) -> io::Result<Self> {
+        if update::parse_tag(version).is_ok() {
+            return Ok(VersionArg::Tag(to_tag(version)));
+        }
+        let req = VersionReq::parse(version).map_err(|e| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("invalid version requirement '{}' ({})", version, e),
+            )
+        })?;
+        Ok(VersionArg::Req(req))
+    }
+
+    fn matches(&self, tag_name: &str) -> bool {
+        match self {
+            VersionArg::Tag(v) => v == tag_name,
+            VersionArg::Req(req) => update::parse_tag(tag_name)
+                .map(|v| req.matches(&v))
+                .unwrap_or(false),
+        }
+    }
+
+    /// Resolves to a release tag, against the published stable releases
+    /// if it is a version requirement.
+    async fn resolve(version: Option<&Self>, project: Project) -> io::Result<String> {
+        match version {
+            Some(VersionArg::Tag(v)) => Ok(v.clone()),
+            Some(VersionArg::Req(req)) => update::resolve_tag(project, Some(req)).await,
+            None => update::resolve_tag(project, None).await,
+        }
+    }
+}
+
+fn platform(sub: &ArgMatches) -> io::Result<Platform> {
+    Platform::new_or_detect(
+        sub.get_one::<Os>("OS").copied(),
+        sub.get_one::<Arch>("ARCH").copied(),
+    )
+}
+
+/// Resolves the "--version" argument to a release tag, or to the pinned
+/// release tag if the lockfile has one for the platform.
+async fn resolve_tag(
+    project: Project,
+    sub: &ArgMatches,
+    lock_file: Option<&Path>,
+) -> io::Result<String> {
+    let version = sub
+        .get_one::<String>("VERSION")
+        .map(|v| VersionArg::parse(v))
+        .transpose()?;
+    if let Some(pin) = lock::find_pin(lock_file, project, platform(sub)?, None)? {
+        if version.as_ref().map_or(true, |v| v.matches(&pin.tag_name)) {
+            return Ok(pin.tag_name);
+        }
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "{} is locked to {}, run update-lock to move the pin",
+                project, pin.tag_name
+            ),
+        ));
+    }
+    VersionArg::resolve(version.as_ref(), project).await
+}
+
+fn install_options(
+    sub: &ArgMatches,
+    tag_name: String,
+    lock_file: Option<&Path>,
+) -> install::Options {
+    install::Options {
+        arch: sub.get_one::<Arch>("ARCH").copied(),
+        os: sub.get_one::<Os>("OS").copied(),
+        release_tag: Some(tag_name),
+        verify_version: true,
+        lock_file: lock_file.map(Path::to_path_buf),
+        workspace_root: sub.get_one::<String>("WORKSPACE_ROOT").map(PathBuf::from),
+        keep_workspace: sub.get_flag("KEEP_WORKSPACE"),
+        ..Default::default()
+    }
+}
+
+async fn update_lock(sub: &ArgMatches, lock_file: Option<&Path>, json: bool) -> io::Result<()> {
+    let project = *sub.get_one::<Project>("PROJECT").unwrap();
+    let lock_file = lock_file.unwrap_or_else(|| Path::new(lock::DEFAULT_LOCK_FILE_NAME));
+    let version = sub
+        .get_one::<String>("VERSION")
+        .map(|v| VersionArg::parse(v))
+        .transpose()?;
+    let tag_name = VersionArg::resolve(version.as_ref(), project).await?;
+    let pin = lock::update(lock_file, project, platform(sub)?, Some(tag_name)).await?;
+    print(json, &pin, || {
+        format!(
+            "locked {} {} for {} in {}",
+            pin.project,
+            pin.tag_name,
+            pin.platform,
+            lock_file.display()
+        )
+    })
+}
+
+async fn install_avalanchego(
+    store: &Store,
+    sub: &ArgMatches,
+    lock_file: Option<&Path>,
+    json: bool,
+) -> io::Result<()> {
+    let tag_name = resolve_tag(Project::AvalancheGo, sub, lock_file).await?;
+    let version_dir = store.version_dir(&tag_name)?;
+    let outcome = avalanchego::github::ensure_installed(
+        &install_options(sub, tag_name.clone(), lock_file),
+        &version_dir.to_string_lossy(),
+    )
+    .await?;
+    if !sub.get_flag("NO_ACTIVATE") {
+        store.activate(&tag_name)?;
+    }
+    print(json, &outcome, || {
+        format!(
+            "installed avalanchego {} at {}{}",
+            outcome.tag_name,
+            outcome.binary_path.display(),
+            if outcome.cache_hit {
+                " (already installed)"
+            } else {
+                ""
+            }
+        )
+    })
+}
+
+async fn install_plugin(
+    store: &Store,
+    sub: &ArgMatches,
+    lock_file: Option<&Path>,
+    json: bool,
+) -> io::Result<()> {
+    let project = *sub.get_one::<Project>("PROJECT").unwrap();
+    if project != Project::SubnetEvm {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("{} is not a VM plugin", project),
+        ));
+    }
+    let vm_id = sub.get_one::<String>("VM_ID").unwrap();
+
+    let avalanchego_tag = match sub.get_one::<String>("AVALANCHEGO_VERSION") {
+        Some(v) => v.clone(),
+        None => store.current_tag()?.ok_or_else(|| {
+            Error::new(
+                ErrorKind::NotFound,
+                format!(
+                    "no active avalanchego in {}, install it first",
+                    store.root_dir.display()
+                ),
+            )
+        })?,
+    };
+    let avalanchego_bin = store.version_dir(&avalanchego_tag)?.join("avalanchego");
+    if !avalanchego_bin.exists() {
+        return Err(Error::new(
+            ErrorKind::NotFound,
+            format!("avalanchego {} is not installed", avalanchego_tag),
+        ));
+    }
+    let plugin_path = Path::new(&avalanchego::get_plugin_dir(&avalanchego_bin)).join(vm_id);
+
+    let tag_name = resolve_tag(project, sub, lock_file).await?;
+    let outcome = subnet_evm::github::ensure_installed(
+        &install_options(sub, tag_name, lock_file),
+        &plugin_path.to_string_lossy(),
+    )
+    .await?;
+    print(json, &outcome, || {
+        format!(
+            "installed {} {} as {} for avalanchego {}",
+            project, outcome.tag_name, vm_id, avalanchego_tag
+        )
+    })
+}
+
+async fn list_releases(sub: &ArgMatches, json: bool) -> io::Result<()> {
+    let project = *sub.get_one::<Project>("PROJECT").unwrap();
+    let prerelease = sub.get_flag("PRERELEASE");
+    let limit = *sub.get_one::<usize>("LIMIT").unwrap();
+
+    let releases: Vec<github::ReleaseResponse> =
+        github::fetch_releases(project.org(), project.repo())
+            .await?
+            .into_iter()
+            .filter(|r| r.tag_name.is_some() && (prerelease || !r.prerelease))
+            .take(limit)
+            .map(|r| github::ReleaseResponse { body: None, ..r })
+            .collect();
+    print(json, &releases, || {
+        releases
+            .iter()
+            .map(|r| {
+                format!(
+                    "{}{}",
+                    r.tag_name.as_deref().unwrap_or_default(),
+                    if r.prerelease { " (prerelease)" } else { "" }
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    })
+}
+
+/// Represents an installed avalanchego version with its plugins.
+#[derive(Debug, Serialize)]
+struct InstalledVersion {
+    tag_name: String,
+    active: bool,
+    avalanchego: Option<InstallReceipt>,
+    /// Installed plugins by VM ID.
+    plugins: Vec<(String, Option<InstallReceipt>)>,
+}
+
+/// Returns the plugin binaries in the plugin directory of the "avalanchego" binary.
+fn plugin_paths(avalanchego_bin: &Path) -> io::Result<Vec<PathBuf>> {
+    let plugin_dir = avalanchego::get_plugin_dir(avalanchego_bin);
+    let entries = match fs::read_dir(plugin_dir) {
+        Ok(v) => v,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut paths = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        if entry.file_type()?.is_file() {
+            paths.push(entry.path());
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+fn list_installed(store: &Store, json: bool) -> io::Result<()> {
+    let current_tag = store.current_tag()?;
+    let mut installed = Vec::new();
+    for tag_name in store.list()? {
+        let avalanchego_bin = store.version_dir(&tag_name)?.join("avalanchego");
+        let mut plugins = Vec::new();
+        for plugin_path in plugin_paths(&avalanchego_bin)? {
+            let vm_id = plugin_path
+                .file_name()
+                .map(|v| v.to_string_lossy().to_string())
+                .unwrap_or_default();
+            plugins.push((vm_id, InstallReceipt::load(&plugin_path)?));
+        }
+        installed.push(InstalledVersion {
+            active: current_tag.as_deref() == Some(tag_name.as_str()),
+            avalanchego: InstallReceipt::load(&avalanchego_bin)?,
+            tag_name,
+            plugins,
+        });
+    }
+    print(json, &installed, || {
+        let mut lines = Vec::new();
+        for v in installed.iter() {
+            lines.push(format!(
+                "{}{}",
+                v.tag_name,
+                if v.active { " (active)" } else { "" }
+            ));
+            for (vm_id, receipt) in v.plugins.iter() {
+                lines.push(format!(
+                    "  {} {}",
+                    vm_id,
+                    receipt
+                        .as_ref()
+                        .map(|m| m.tag_name.as_str())
+                        .unwrap_or("(unknown)")
+                ));
+            }
+        }
+        lines.join("\n")
+    })
+}
+
+fn verify(store: &Store, version: Option<&String>, json: bool) -> io::Result<()> {
+    let tag_name = match version {
+        Some(v) => v.clone(),
+        None => store.current_tag()?.ok_or_else(|| {
+            Error::new(
+                ErrorKind::NotFound,
+                format!("no active avalanchego in {}", store.root_dir.display()),
+            )
+        })?,
+    };
+    let avalanchego_bin = store.version_dir(&tag_name)?.join("avalanchego");
+    let mut verifications = vec![install::verify_installed(&avalanchego_bin)?];
+    for plugin_path in plugin_paths(&avalanchego_bin)? {
+        verifications.push(install::verify_installed(&plugin_path)?);
+    }
+
+    print(json, &verifications, || {
+        verifications
+            .iter()
+            .map(|v: &Verification| {
+                format!(
+                    "{} {} {}",
+                    if v.is_ok() { "OK    " } else { "FAILED" },
+                    v.binary_path.display(),
+                    v.tag_name
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    })?;
+    if verifications.iter().any(|v| !v.is_ok()) {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("verification failed for {}", tag_name),
+        ));
+    }
+    Ok(())
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-installer --bin avalanche-installer -- test_command --exact --show-output
+#[test]
+fn test_command() {
+    command().debug_assert();
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-installer --bin avalanche-installer -- test_version_arg --exact --show-output
+#[test]
+fn test_version_arg() {
+    let tag = |v: &str| VersionArg::Tag(v.to_string());
+    let req = |v: &str| VersionArg::Req(VersionReq::parse(v).unwrap());
+    for (version, expected) in [
+        ("v1.10.3", tag("v1.10.3")),
+        ("1.10.3", tag("v1.10.3")),
+        ("v1.11.0-fuji", tag("v1.11.0-fuji")),
+        ("^1.10", req("^1.10")),
+        ("~1.10.3", req("~1.10.3")),
+        ("=1.10.3", req("=1.10.3")),
+        ("1.10", req("^1.10")),
+        (">=1.10.3, <1.11", req(">=1.10.3, <1.11")),
+    ] {
+        assert_eq!(VersionArg::parse(version).unwrap(), expected, "{}", version);
+    }
+    assert!(VersionArg::parse("latest").is_err());
+    assert!(VersionArg::parse("v1.10").is_err());
+
+    assert!(tag("v1.10.3").matches("v1.10.3"));
+    assert!(!tag("v1.10.3").matches("v1.10.30"));
+    assert!(req("^1.10").matches("v1.10.30"));
+    assert!(!req("^1.10").matches("v2.0.0"));
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-installer --bin avalanche-installer -- test_installed_version_arg --exact --show-output
+#[test]
+fn test_installed_version_arg() {
+    assert_eq!(parse_installed_tag("1.10.3").unwrap(), "v1.10.3");
+    assert_eq!(parse_installed_tag("v1.11.0-fuji").unwrap(), "v1.11.0-fuji");
+    for version in [
+        "v1/../..",
+        "v1.10.3/.",
+        "v1.10.3/",
+        "/etc",
+        "v1.10.3/../../..",
+    ] {
+        let err = parse_installed_tag(version).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput, "{}", version);
+    }
+
+    for args in [
+        ["avalanche-installer", "uninstall", "v1/../.."],
+        ["avalanche-installer", "use", "../.."],
+        ["avalanche-installer", "verify", "v1/.."],
+    ] {
+        assert!(command().try_get_matches_from(args).is_err(), "{:?}", args);
+    }
+    let matches = command()
+        .try_get_matches_from(["avalanche-installer", "uninstall", "1.10.3"])
+        .unwrap();
+    let (_, sub) = matches.subcommand().unwrap();
+    assert_eq!(sub.get_one::<String>("VERSION").unwrap(), "v1.10.3");
+}
@@ -0,0 +1,56 @@
+use std::io;
+
+use super::block_on;
+use crate::{
+    avalanchego::{config::Config, github},
+    install::{InstallOutcome, Options},
+    platform::{Arch, Os},
+};
+
+/// Same as "avalanchego::github::download_latest", but blocks the current thread.
+pub fn download_latest(arch: Option<Arch>, os: Option<Os>) -> io::Result<InstallOutcome> {
+    block_on(github::download_latest(arch, os))?
+}
+
+/// Same as "avalanchego::github::download", but blocks the current thread.
+pub fn download(
+    arch: Option<Arch>,
+    os: Option<Os>,
+    release_tag: Option<String>,
+) -> io::Result<InstallOutcome> {
+    block_on(github::download(arch, os, release_tag))?
+}
+
+/// Same as "avalanchego::github::download_with_options", but blocks the current thread.
+pub fn download_with_options(opts: &Options) -> io::Result<InstallOutcome> {
+    block_on(github::download_with_options(opts))?
+}
+
+/// Same as "avalanchego::github::install", but blocks the current thread.
+pub fn install(
+    arch: Option<Arch>,
+    os: Option<Os>,
+    release_tag: Option<String>,
+    target_dir: &str,
+) -> io::Result<InstallOutcome> {
+    block_on(github::install(arch, os, release_tag, target_dir))?
+}
+
+/// Same as "avalanchego::github::install_with_options", but blocks the current thread.
+pub fn install_with_options(opts: &Options, target_dir: &str) -> io::Result<InstallOutcome> {
+    block_on(github::install_with_options(opts, target_dir))?
+}
+
+/// Same as "avalanchego::github::install_with_config", but blocks the current thread.
+pub fn install_with_config(
+    opts: &Options,
+    target_dir: &str,
+    config: &Config,
+) -> io::Result<InstallOutcome> {
+    block_on(github::install_with_config(opts, target_dir, config))?
+}
+
+/// Same as "avalanchego::github::ensure_installed", but blocks the current thread.
+pub fn ensure_installed(opts: &Options, target_dir: &str) -> io::Result<InstallOutcome> {
+    block_on(github::ensure_installed(opts, target_dir))?
+}
@@ -0,0 +1,162 @@
+#[cfg(feature = "avalanchego")]
+pub mod avalanchego;
+#[cfg(feature = "subnet_evm")]
+pub mod subnet_evm;
+
+use std::{
+    future::Future,
+    io::{self, Error, ErrorKind},
+    path::Path,
+};
+
+use semver::VersionReq;
+use tokio::runtime::{Builder, Handle};
+
+pub use crate::install::{verify_installed, Verification};
+use crate::{
+    batch::{BatchOptions, Target, TargetResult},
+    github::ReleaseResponse,
+    lock::Pin,
+    platform::Platform,
+    project::Project,
+    update::UpdateCheck,
+};
+
+/// Runs the future to completion on a new single-threaded runtime,
+/// so that the sync callers do not need to set up their own.
+/// Returns an error if called from within an async runtime,
+/// where the async functions must be used instead.
+fn block_on<F: Future>(f: F) -> io::Result<F::Output> {
+    if Handle::try_current().is_ok() {
+        return Err(Error::new(
+            ErrorKind::Other,
+            "blocking API called from within an async runtime, use the async API instead",
+        ));
+    }
+    let rt = Builder::new_current_thread().enable_all().build()?;
+    Ok(rt.block_on(f))
+}
+
+/// Same as "github::fetch_latest_release", but blocks the current thread.
+pub fn fetch_latest_release(org: &str, repo: &str) -> io::Result<ReleaseResponse> {
+    block_on(crate::github::fetch_latest_release(org, repo))?
+}
+
+/// Same as "github::fetch_release", but blocks the current thread.
+pub fn fetch_release(org: &str, repo: &str, tag_name: &str) -> io::Result<ReleaseResponse> {
+    block_on(crate::github::fetch_release(org, repo, tag_name))?
+}
+
+/// Same as "github::fetch_releases", but blocks the current thread.
+pub fn fetch_releases(org: &str, repo: &str) -> io::Result<Vec<ReleaseResponse>> {
+    block_on(crate::github::fetch_releases(org, repo))?
+}
+
+/// Same as "update::resolve_tag", but blocks the current thread.
+pub fn resolve_tag(project: Project, req: Option<&VersionReq>) -> io::Result<String> {
+    block_on(crate::update::resolve_tag(project, req))?
+}
+
+/// Same as "update::check_for_update", but blocks the current thread.
+pub fn check_for_update<P: AsRef<Path>>(installed_path: P) -> io::Result<UpdateCheck> {
+    block_on(crate::update::check_for_update(installed_path))?
+}
+
+/// Same as "lock::update", but blocks the current thread.
+pub fn update_lock<P: AsRef<Path>>(
+    lock_file: P,
+    project: Project,
+    platform: Platform,
+    release_tag: Option<String>,
+) -> io::Result<Pin> {
+    block_on(crate::lock::update(
+        lock_file,
+        project,
+        platform,
+        release_tag,
+    ))?
+}
+
+/// Same as "batch::install_all", but blocks the current thread.
+/// The downloads still overlap, but the unpacking and hashing
+/// run on the current thread, one target at a time.
+pub fn install_all(
+    targets: Vec<Target>,
+    batch_opts: &BatchOptions,
+) -> io::Result<Vec<TargetResult>> {
+    block_on(crate::batch::install_all(targets, batch_opts))?
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-installer --features blocking --lib -- blocking::test_blocking --exact --show-output
+#[cfg(feature = "subnet_evm")]
+#[test]
+fn test_blocking() {
+    use crate::{
+        install::{record_fake_install, FakeRelease, Options},
+        platform::{Arch, Os},
+    };
+    use std::fs;
+
+    let _ = env_logger::builder()
+        .filter_level(log::LevelFilter::Info)
+        .is_test(true)
+        .try_init();
+
+    // must not be nested in an async runtime
+    let rt = Builder::new_current_thread().build().unwrap();
+    let err = rt.block_on(async { block_on(async {}).unwrap_err() });
+    assert!(err.to_string().contains("async runtime"), "{}", err);
+    let err = rt.block_on(async { fetch_releases("ava-labs", "subnet-evm").unwrap_err() });
+    assert!(err.to_string().contains("async runtime"), "{}", err);
+    assert_eq!(block_on(async { 1 }).unwrap(), 1);
+
+    let root_dir = Path::new(&random_manager::tmp_path(10, None).unwrap()).to_path_buf();
+    fs::create_dir_all(&root_dir).unwrap();
+
+    // already installed for another platform, so nothing is downloaded
+    let binary_path = root_dir.join("vm1");
+    fs::write(&binary_path, "vm1").unwrap();
+    let platform = Platform::new(Os::MacOs, Arch::Arm64);
+    record_fake_install(Project::SubnetEvm, &binary_path, "v0.5.1", platform);
+    let opts = Options {
+        os: Some(platform.os),
+        arch: Some(platform.arch),
+        release_tag: Some(String::from("v0.5.1")),
+        ..Default::default()
+    };
+    let outcome = subnet_evm::ensure_installed(&opts, &binary_path.to_string_lossy()).unwrap();
+    assert!(outcome.cache_hit);
+
+    // pinned, so that nothing is fetched from GitHub,
+    // and served from plain threads, outside of any runtime
+    let platform = Platform::detect().unwrap();
+    let lock_file = root_dir.join("avalanche-installer.lock");
+    FakeRelease::new("v0.5.1").serve(&lock_file, platform);
+
+    let binary_path = root_dir.join("vm2");
+    let opts = Options {
+        os: Some(platform.os),
+        arch: Some(platform.arch),
+        lock_file: Some(lock_file),
+        verify_version: true,
+        ..Default::default()
+    };
+    let outcome = subnet_evm::ensure_installed(&opts, &binary_path.to_string_lossy()).unwrap();
+    assert!(!outcome.cache_hit);
+    assert!(outcome.bytes_downloaded > 0);
+    assert_eq!(outcome.version.unwrap().version, "0.5.1");
+    assert!(verify_installed(&binary_path).unwrap().is_ok());
+
+    let results = install_all(
+        vec![Target::new(
+            Project::SubnetEvm,
+            &binary_path.to_string_lossy(),
+            opts.clone(),
+        )],
+        &BatchOptions::default(),
+    )
+    .unwrap();
+    assert!(results[0].result.as_ref().unwrap().cache_hit);
+
+    fs::remove_dir_all(&root_dir).unwrap();
+}
@@ -0,0 +1,37 @@
+use std::io;
+
+use super::block_on;
+use crate::{
+    install::{InstallOutcome, Options},
+    platform::{Arch, Os},
+    subnet_evm::github,
+};
+
+/// Same as "subnet_evm::github::download_latest", but blocks the current thread.
+pub fn download_latest(
+    arch: Option<Arch>,
+    os: Option<Os>,
+    target_file_path: &str,
+) -> io::Result<InstallOutcome> {
+    block_on(github::download_latest(arch, os, target_file_path))?
+}
+
+/// Same as "subnet_evm::github::download", but blocks the current thread.
+pub fn download(
+    arch: Option<Arch>,
+    os: Option<Os>,
+    release_tag: Option<String>,
+    target_file_path: &str,
+) -> io::Result<InstallOutcome> {
+    block_on(github::download(arch, os, release_tag, target_file_path))?
+}
+
+/// Same as "subnet_evm::github::download_with_options", but blocks the current thread.
+pub fn download_with_options(opts: &Options, target_file_path: &str) -> io::Result<InstallOutcome> {
+    block_on(github::download_with_options(opts, target_file_path))?
+}
+
+/// Same as "subnet_evm::github::ensure_installed", but blocks the current thread.
+pub fn ensure_installed(opts: &Options, target_file_path: &str) -> io::Result<InstallOutcome> {
+    block_on(github::ensure_installed(opts, target_file_path))?
+}
@@ -0,0 +1,208 @@
+use std::{
+    error,
+    fmt::{self, Display},
+    future::Future,
+    io::{self, Error, ErrorKind, Read, Seek, SeekFrom},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use tokio::sync::Notify;
+
+/// Represents the token to cancel in-progress installs.
+/// Clones share the same state, so the caller keeps one clone
+/// and passes the other in the options (see "install::Options").
+/// Once cancelled, the downloads, the release fetch retries, and the
+/// unpacking stop, the workspace is removed, and the install fails
+/// with the "Cancelled" error.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    cancelled: AtomicBool,
+    notify: Notify,
+}
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels all operations using this token, or any of its clones.
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::SeqCst);
+        self.inner.notify.notify_waiters();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Returns the "Cancelled" error if cancelled.
+    pub fn check(&self) -> io::Result<()> {
+        if self.is_cancelled() {
+            return Err(cancelled());
+        }
+        Ok(())
+    }
+
+    /// Completes when cancelled.
+    pub async fn cancelled(&self) {
+        loop {
+            let notified = self.inner.notify.notified();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    /// Runs the future until it completes, or until cancelled,
+    /// in which case the future is dropped and the "Cancelled" error is returned.
+    pub async fn run<T, F>(&self, f: F) -> io::Result<T>
+    where
+        F: Future<Output = io::Result<T>>,
+    {
+        tokio::select! {
+            biased;
+            _ = self.cancelled() => Err(cancelled()),
+            res = f => res,
+        }
+    }
+
+    /// Wraps the reader so that reads fail with the "Cancelled" error once cancelled.
+    pub fn reader<R: Read>(&self, r: R) -> CancelReader<R> {
+        CancelReader {
+            r,
+            cancel: self.clone(),
+        }
+    }
+}
+
+/// Represents the reader that stops once the token is cancelled.
+pub struct CancelReader<R> {
+    r: R,
+    cancel: CancelToken,
+}
+
+impl<R: Read> Read for CancelReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.cancel.check()?;
+        self.r.read(buf)
+    }
+}
+
+impl<R: Seek> Seek for CancelReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.cancel.check()?;
+        self.r.seek(pos)
+    }
+}
+
+/// Represents the error of a cancelled install, wrapped in the "io::Error".
+/// Not "ErrorKind::Interrupted", which "io::copy" and "Read::read_exact" retry on.
+/// See "is_cancelled".
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Cancelled;
+
+impl Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cancelled")
+    }
+}
+
+impl error::Error for Cancelled {}
+
+/// Returns the "Cancelled" error.
+pub fn cancelled() -> Error {
+    Error::new(ErrorKind::Other, Cancelled)
+}
+
+/// Returns true if the error is the "Cancelled" error.
+pub fn is_cancelled(e: &Error) -> bool {
+    e.get_ref().map(|e| e.is::<Cancelled>()).unwrap_or(false)
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-installer --lib -- cancel::test_cancel --exact --show-output
+#[cfg(feature = "subnet_evm")]
+#[tokio::test]
+async fn test_cancel() {
+    use crate::{
+        install::{FakeRelease, Options},
+        platform::Platform,
+    };
+    use std::{fs, path::Path, time::Duration};
+    use tokio::time::sleep;
+
+    let _ = env_logger::builder()
+        .filter_level(log::LevelFilter::Info)
+        .is_test(true)
+        .try_init();
+
+    let cancel = CancelToken::new();
+    assert!(cancel.check().is_ok());
+    let cloned = cancel.clone();
+    tokio::spawn(async move {
+        sleep(Duration::from_millis(100)).await;
+        cloned.cancel();
+    });
+    let err = cancel
+        .run(async {
+            sleep(Duration::from_secs(60)).await;
+            Ok(())
+        })
+        .await
+        .unwrap_err();
+    assert!(is_cancelled(&err));
+    assert!(cancel.check().is_err());
+    assert!(cancel.reader(&b"data"[..]).read(&mut [0u8; 4]).is_err());
+    assert!(!is_cancelled(&Error::new(
+        ErrorKind::Interrupted,
+        "aborted"
+    )));
+
+    // pinned, so that nothing is fetched from GitHub,
+    // and serves 100 bytes of the archive, and then stalls
+    let root_dir = Path::new(&random_manager::tmp_path(10, None).unwrap()).to_path_buf();
+    let workspace_root = root_dir.join("workspace");
+    fs::create_dir_all(&workspace_root).unwrap();
+    let platform = Platform::parse("linux/amd64").unwrap();
+    let lock_file = root_dir.join("avalanche-installer.lock");
+    FakeRelease {
+        stall_after: Some(100),
+        ..FakeRelease::new("v0.5.1")
+    }
+    .serve(&lock_file, platform);
+
+    let cancel = CancelToken::new();
+    let opts = Options {
+        os: Some(platform.os),
+        arch: Some(platform.arch),
+        lock_file: Some(lock_file),
+        workspace_root: Some(workspace_root.clone()),
+        cancel: cancel.clone(),
+        ..Default::default()
+    };
+    let target_file_path = root_dir.join("subnet-evm").to_string_lossy().to_string();
+    let install = tokio::spawn(async move {
+        crate::subnet_evm::github::download_with_options(&opts, &target_file_path).await
+    });
+    sleep(Duration::from_millis(500)).await;
+    cancel.cancel();
+
+    let err = tokio::time::timeout(Duration::from_secs(5), install)
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap_err();
+    assert!(is_cancelled(&err), "{}", err);
+    assert_eq!(fs::read_dir(&workspace_root).unwrap().count(), 0);
+    assert!(!root_dir.join("subnet-evm").exists());
+
+    fs::remove_dir_all(&root_dir).unwrap();
+}
@@ -0,0 +1,128 @@
+use std::{
+    ffi::CString,
+    io::{self, Error, ErrorKind},
+    mem::MaybeUninit,
+    os::unix::{ffi::OsStrExt, fs::MetadataExt},
+    path::Path,
+};
+
+use crate::extract::Limits;
+
+/// Estimated ratio of the unpacked size to the release archive size.
+/// The release archives unpack to about 2-3x their size.
+pub const UNPACK_FACTOR: u64 = 4;
+
+/// Returns the bytes available to unprivileged users on the filesystem of the "path".
+/// If the "path" does not exist yet, its nearest existing ancestor is checked.
+/// ref. <https://man7.org/linux/man-pages/man3/statvfs.3.html>
+pub fn available_bytes<P: AsRef<Path>>(path: P) -> io::Result<u64> {
+    let path = crate::workspace::existing_ancestor(path.as_ref());
+    let c_path = CString::new(path.as_os_str().as_bytes())
+        .map_err(|e| Error::new(ErrorKind::InvalidInput, format!("invalid path {}", e)))?;
+
+    let mut stat = MaybeUninit::<libc::statvfs>::uninit();
+    // SAFETY: "c_path" is a valid C string, and "stat" is written on success
+    let ret = unsafe { libc::statvfs(c_path.as_ptr(), stat.as_mut_ptr()) };
+    if ret != 0 {
+        let e = Error::last_os_error();
+        return Err(Error::new(
+            e.kind(),
+            format!("failed statvfs {} ({})", path.display(), e),
+        ));
+    }
+    let stat = unsafe { stat.assume_init() };
+    #[allow(clippy::unnecessary_cast)]
+    Ok(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+/// Returns an error if any filesystem does not have the required bytes available.
+/// Each requirement is the path and the bytes to be written under it.
+/// Requirements on the same filesystem add up.
+pub fn ensure_available(required: &[(&Path, u64)]) -> io::Result<()> {
+    // (device id, first path on the device, total required bytes)
+    let mut per_fs: Vec<(u64, &Path, u64)> = Vec::new();
+    for (path, bytes) in required {
+        let dev = crate::workspace::existing_ancestor(path).metadata()?.dev();
+        match per_fs.iter_mut().find(|(d, _, _)| *d == dev) {
+            Some((_, _, total)) => *total = total.saturating_add(*bytes),
+            None => per_fs.push((dev, path, *bytes)),
+        }
+    }
+
+    for (_, path, required_bytes) in per_fs {
+        let available = available_bytes(path)?;
+        log::info!(
+            "{} requires {} bytes, {} bytes available",
+            path.display(),
+            required_bytes,
+            available
+        );
+        if required_bytes > available {
+            return Err(Error::new(
+                ErrorKind::Other,
+                format!(
+                    "not enough disk space on {}: required {} bytes, available {} bytes",
+                    path.display(),
+                    required_bytes,
+                    available
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Checks the free space before downloading an "archive_bytes" release archive
+/// to the "workspace_dir", unpacking it there, and copying the unpacked files
+/// to the "target_dir" (if any). The unpacked size is estimated with
+/// "UNPACK_FACTOR", and capped by the unpack "limits".
+pub fn preflight(
+    archive_bytes: u64,
+    workspace_dir: &Path,
+    target_dir: Option<&Path>,
+    limits: &Limits,
+) -> io::Result<()> {
+    let unpacked_bytes = archive_bytes
+        .saturating_mul(UNPACK_FACTOR)
+        .min(limits.max_total_bytes);
+    let mut required = vec![(workspace_dir, archive_bytes.saturating_add(unpacked_bytes))];
+    if let Some(target_dir) = target_dir {
+        required.push((target_dir, unpacked_bytes));
+    }
+    ensure_available(&required)
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-installer --lib -- disk::test_preflight --exact --show-output
+#[test]
+fn test_preflight() {
+    let _ = env_logger::builder()
+        .filter_level(log::LevelFilter::Info)
+        .is_test(true)
+        .try_init();
+
+    let dir = std::env::temp_dir();
+    let available = available_bytes(&dir).unwrap();
+    assert!(available > 0);
+    // nonexistent paths are checked on their existing ancestor
+    assert!(available_bytes(dir.join("not-yet").join("created")).is_ok());
+
+    let limits = Limits::default();
+    preflight(1024, &dir, Some(&dir), &limits).unwrap();
+
+    let err = ensure_available(&[(&dir, available * 2)]).unwrap_err();
+    assert!(err.to_string().contains("required"), "{}", err);
+    // requirements on the same filesystem add up
+    assert!(ensure_available(&[(&dir, available), (&dir, available)]).is_err());
+
+    // the unpacked size is capped by the limits
+    let unlimited = Limits {
+        max_total_bytes: u64::MAX,
+        ..Default::default()
+    };
+    assert!(preflight(available / 4, &dir, Some(&dir), &unlimited).is_err());
+    let limits = Limits {
+        max_total_bytes: 1,
+        ..Default::default()
+    };
+    preflight(available / 4, &dir, Some(&dir), &limits).unwrap();
+}
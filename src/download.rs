@@ -0,0 +1,259 @@
+use std::{
+    fs::File,
+    io::{self, Error, ErrorKind, Write},
+    time::Duration,
+};
+
+use reqwest::{Client, ClientBuilder};
+use sha2::{Digest, Sha256};
+use tokio::time::{timeout_at, Instant};
+
+/// Represents the downloaded file.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct DownloadedFile {
+    /// Number of bytes written to the file.
+    pub bytes: u64,
+    /// Hex-encoded SHA-256 digest of the file.
+    pub sha256: String,
+}
+
+/// Represents the timeouts for downloading release assets,
+/// so that a stalled connection does not hang the install.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Timeouts {
+    /// Maximum time to establish the connection.
+    pub connect: Duration,
+    /// Maximum time for the whole download, from connecting to the last byte.
+    /// Leave empty for no limit.
+    pub overall: Option<Duration>,
+    /// Aborts the download if fewer than "low_speed_limit" bytes per second
+    /// are received for "low_speed_time", including when the connection
+    /// goes idle. Set "low_speed_limit" to 0 to disable.
+    pub low_speed_limit: u64,
+    pub low_speed_time: Duration,
+}
+
+impl Default for Timeouts {
+    fn default() -> Self {
+        Self {
+            connect: Duration::from_secs(30),
+            overall: Some(Duration::from_secs(30 * 60)),
+            low_speed_limit: 1024,
+            low_speed_time: Duration::from_secs(60),
+        }
+    }
+}
+
+/// Creates the HTTP client to download release assets with.
+/// Share the client across downloads to reuse its connection pool.
+pub fn new_client() -> io::Result<Client> {
+    new_client_with_timeouts(&Timeouts::default())
+}
+
+/// Same as "new_client" but with the "connect" timeout.
+/// The other timeouts are applied per download.
+pub fn new_client_with_timeouts(timeouts: &Timeouts) -> io::Result<Client> {
+    client_builder()
+        .connect_timeout(timeouts.connect)
+        .build()
+        .map_err(|e| {
+            Error::new(
+                ErrorKind::Other,
+                format!("failed ClientBuilder build {}", e),
+            )
+        })
+}
+
+/// Returns the HTTP client builder with the user agent and the TLS backend,
+/// preferring rustls if both the "rustls" and "native-tls" features are enabled.
+pub(crate) fn client_builder() -> ClientBuilder {
+    let builder = ClientBuilder::new().user_agent(env!("CARGO_PKG_NAME"));
+    #[cfg(feature = "rustls")]
+    let builder = builder.use_rustls_tls();
+    builder
+}
+
+/// Downloads a file to the "file_path", computing its SHA-256 digest
+/// while streaming the response body to the file.
+pub async fn download_file(ep: &str, file_path: &str) -> io::Result<DownloadedFile> {
+    download_file_with_client(&new_client()?, ep, file_path).await
+}
+
+/// Same as "download_file" but with the shared HTTP client.
+pub async fn download_file_with_client(
+    cli: &Client,
+    ep: &str,
+    file_path: &str,
+) -> io::Result<DownloadedFile> {
+    download_file_with_preflight(cli, ep, file_path, &Timeouts::default(), |_| Ok(())).await
+}
+
+/// Same as "download_file_with_client", but with the "timeouts", and calls
+/// the "preflight" with the "Content-Length" of the response (if any)
+/// before writing the file, aborting the download if it returns an error
+/// (e.g., "disk::preflight"). Fails with "ErrorKind::TimedOut" on timeouts.
+pub async fn download_file_with_preflight<F>(
+    cli: &Client,
+    ep: &str,
+    file_path: &str,
+    timeouts: &Timeouts,
+    preflight: F,
+) -> io::Result<DownloadedFile>
+where
+    F: FnOnce(Option<u64>) -> io::Result<()>,
+{
+    log::info!("downloading the file via {}", ep);
+    let mut req = cli.get(ep);
+    if let Some(overall) = timeouts.overall {
+        req = req.timeout(overall);
+    }
+    let mut resp = req
+        .send()
+        .await
+        .map_err(|e| reqwest_error("failed reqwest::get", e))?
+        .error_for_status()
+        .map_err(|e| reqwest_error("failed reqwest::get", e))?;
+
+    preflight(resp.content_length())?;
+
+    let mut f = File::create(file_path)?;
+    let mut hasher = Sha256::new();
+    let mut bytes = 0_u64;
+
+    // throughput is measured over windows of "low_speed_time"
+    let mut window_start = Instant::now();
+    let mut window_bytes = 0_u64;
+    loop {
+        let chunk = if timeouts.low_speed_limit > 0 {
+            let deadline = window_start + timeouts.low_speed_time;
+            let chunk = timeout_at(deadline, resp.chunk()).await;
+            let now = Instant::now();
+            if now >= deadline {
+                let elapsed = now.duration_since(window_start);
+                let bytes_per_sec = window_bytes as f64 / elapsed.as_secs_f64();
+                if bytes_per_sec < timeouts.low_speed_limit as f64 {
+                    return Err(Error::new(
+                        ErrorKind::TimedOut,
+                        format!(
+                            "download from {} too slow: {:.0} bytes/s for {:?}, below {} bytes/s",
+                            ep, bytes_per_sec, elapsed, timeouts.low_speed_limit
+                        ),
+                    ));
+                }
+                window_start = now;
+                window_bytes = 0;
+            }
+            match chunk {
+                Ok(v) => v,
+                // the window was fast enough, but ended while waiting
+                Err(_) => continue,
+            }
+        } else {
+            resp.chunk().await
+        };
+
+        let chunk = match chunk.map_err(|e| reqwest_error("failed chunk", e))? {
+            Some(v) => v,
+            None => break,
+        };
+        hasher.update(&chunk);
+        f.write_all(&chunk)?;
+        bytes += chunk.len() as u64;
+        window_bytes += chunk.len() as u64;
+    }
+    f.sync_all()?;
+
+    let sha256 = hex::encode(hasher.finalize());
+    log::info!(
+        "downloaded {} bytes to {} (sha256 {})",
+        bytes,
+        file_path,
+        sha256
+    );
+    Ok(DownloadedFile { bytes, sha256 })
+}
+
+fn reqwest_error(msg: &str, e: reqwest::Error) -> Error {
+    let kind = if e.is_timeout() {
+        ErrorKind::TimedOut
+    } else {
+        ErrorKind::Other
+    };
+    Error::new(kind, format!("{} {}", msg, e))
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-installer --lib -- download::test_download_timeouts --exact --show-output
+#[tokio::test]
+async fn test_download_timeouts() {
+    use tokio::{io::AsyncWriteExt, net::TcpListener, time::sleep};
+
+    let _ = env_logger::builder()
+        .filter_level(log::LevelFilter::Info)
+        .is_test(true)
+        .try_init();
+
+    // serves 100 bytes of the 1000-byte body, and then stalls
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let ep = format!(
+        "http://{}/avalanchego.tar.gz",
+        listener.local_addr().unwrap()
+    );
+    tokio::spawn(async move {
+        loop {
+            let (mut conn, _) = listener.accept().await.unwrap();
+            tokio::spawn(async move {
+                let _ = conn
+                    .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 1000\r\n\r\n")
+                    .await;
+                let _ = conn.write_all(&[0u8; 100]).await;
+                sleep(Duration::from_secs(60)).await;
+            });
+        }
+    });
+
+    let file_path = random_manager::tmp_path(10, None).unwrap();
+    let cli = new_client().unwrap();
+    let download = |timeouts: Timeouts| {
+        let (cli, ep, file_path) = (cli.clone(), ep.clone(), file_path.clone());
+        async move {
+            let started = Instant::now();
+            let res = download_file_with_preflight(&cli, &ep, &file_path, &timeouts, |n| {
+                assert_eq!(n, Some(1000));
+                Ok(())
+            })
+            .await;
+            (res, started.elapsed())
+        }
+    };
+
+    let (res, elapsed) = download(Timeouts {
+        low_speed_limit: 1024,
+        low_speed_time: Duration::from_millis(500),
+        ..Default::default()
+    })
+    .await;
+    let err = res.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::TimedOut, "{}", err);
+    assert!(elapsed < Duration::from_secs(5));
+    log::info!("low speed: {}", err);
+
+    let (res, elapsed) = download(Timeouts {
+        overall: Some(Duration::from_millis(500)),
+        low_speed_limit: 0,
+        ..Default::default()
+    })
+    .await;
+    let err = res.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::TimedOut, "{}", err);
+    assert!(elapsed < Duration::from_secs(5));
+    log::info!("overall: {}", err);
+
+    // fails before writing anything
+    std::fs::remove_file(&file_path).unwrap();
+    let res = download_file_with_preflight(&cli, &ep, &file_path, &Timeouts::default(), |_| {
+        Err(Error::new(ErrorKind::Other, "not enough disk space"))
+    })
+    .await;
+    assert!(res.is_err());
+    assert!(!std::path::Path::new(&file_path).exists());
+}
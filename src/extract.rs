@@ -0,0 +1,607 @@
+use std::{
+    fs::{self, File},
+    io::{self, Error, ErrorKind, Read},
+    os::unix::fs::{symlink, PermissionsExt},
+    path::{Component, Path, PathBuf},
+};
+
+use crate::cancel::CancelToken;
+use flate2::read::GzDecoder;
+use tar::{Archive, EntryType};
+use zip::ZipArchive;
+
+/// Represents the archive type of a release asset.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ArchiveKind {
+    TarGz,
+    Zip,
+}
+
+impl ArchiveKind {
+    /// Detects the archive type from the file name or the URL
+    /// (e.g., "avalanchego-linux-amd64-v1.10.3.tar.gz", "avalanchego-macos-v1.10.3.zip").
+    pub fn from_file_name(file_name: &str) -> io::Result<Self> {
+        if file_name.ends_with(".tar.gz") {
+            Ok(ArchiveKind::TarGz)
+        } else if file_name.ends_with(".zip") {
+            Ok(ArchiveKind::Zip)
+        } else {
+            Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("unknown archive type '{}'", file_name),
+            ))
+        }
+    }
+
+    /// Returns the file name suffix (e.g., ".tar.gz").
+    pub fn suffix(&self) -> &'static str {
+        match self {
+            ArchiveKind::TarGz => ".tar.gz",
+            ArchiveKind::Zip => ".zip",
+        }
+    }
+}
+
+/// Represents the limits on the unpacked archive, to stop decompression bombs.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Limits {
+    /// Maximum total size in bytes of the unpacked files.
+    pub max_total_bytes: u64,
+    /// Maximum number of entries in the archive.
+    pub max_entries: u64,
+}
+
+impl Default for Limits {
+    /// The official release archives unpack to a few hundred MiB at most.
+    fn default() -> Self {
+        Self {
+            max_total_bytes: 2 * 1024 * 1024 * 1024,
+            max_entries: 10_000,
+        }
+    }
+}
+
+/// Unpacks the "tar.gz" or "zip" archive to the "dst_dir", which is created if missing.
+///
+/// The archive is not trusted:
+/// - entries with absolute paths or paths escaping "dst_dir" ("../") are rejected,
+/// - symlinks must be relative and resolve within "dst_dir",
+/// - hard links must point to another entry within "dst_dir",
+/// - the unpacked size and the number of entries are capped by the "limits".
+///
+/// Any violation fails the whole unpack with "ErrorKind::InvalidData".
+pub fn unpack<P: AsRef<Path>, Q: AsRef<Path>>(
+    archive_path: P,
+    dst_dir: Q,
+    archive_kind: ArchiveKind,
+    limits: &Limits,
+) -> io::Result<()> {
+    unpack_with_cancel(
+        archive_path,
+        dst_dir,
+        archive_kind,
+        limits,
+        &CancelToken::new(),
+    )
+}
+
+/// Same as "unpack", but stops with the "cancel::Cancelled" error once cancelled.
+pub fn unpack_with_cancel<P: AsRef<Path>, Q: AsRef<Path>>(
+    archive_path: P,
+    dst_dir: Q,
+    archive_kind: ArchiveKind,
+    limits: &Limits,
+    cancel: &CancelToken,
+) -> io::Result<()> {
+    let (archive_path, dst_dir) = (archive_path.as_ref(), dst_dir.as_ref());
+    cancel.check()?;
+    fs::create_dir_all(dst_dir)?;
+    let mut unpacker = Unpacker {
+        dst_dir,
+        cancel,
+        limits,
+        entries: 0,
+        total_bytes: 0,
+    };
+    let res = match archive_kind {
+        ArchiveKind::TarGz => unpacker.unpack_tar_gz(archive_path),
+        ArchiveKind::Zip => unpacker.unpack_zip(archive_path),
+    };
+    // the archive readers wrap the "Cancelled" error in their own
+    match res {
+        Err(_) if cancel.is_cancelled() => Err(crate::cancel::cancelled()),
+        res => res,
+    }
+}
+
+struct Unpacker<'a> {
+    dst_dir: &'a Path,
+    cancel: &'a CancelToken,
+    limits: &'a Limits,
+    entries: u64,
+    total_bytes: u64,
+}
+
+impl Unpacker<'_> {
+    fn unpack_tar_gz(&mut self, archive_path: &Path) -> io::Result<()> {
+        let mut archive = Archive::new(GzDecoder::new(
+            self.cancel.reader(File::open(archive_path)?),
+        ));
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            self.count_entry()?;
+
+            let entry_path = entry.path()?.to_path_buf();
+            let output_path = match self.output_path(&entry_path)? {
+                Some(v) => v,
+                None => continue,
+            };
+            match entry.header().entry_type() {
+                EntryType::Directory => fs::create_dir_all(&output_path)?,
+                EntryType::Regular | EntryType::Continuous | EntryType::GNUSparse => {
+                    let mode = entry.header().mode().unwrap_or(0o755);
+                    self.write_file(&mut entry, &output_path, mode)?;
+                }
+                EntryType::Symlink => {
+                    let target = entry.link_name()?.unwrap_or_default().to_path_buf();
+                    self.create_symlink(&entry_path, &target, &output_path)?;
+                }
+                EntryType::Link => {
+                    let target = entry.link_name()?.unwrap_or_default().to_path_buf();
+                    let target_path = self.output_path(&target)?.ok_or_else(|| {
+                        invalid(format!("hard link {} has no target", entry_path.display()))
+                    })?;
+                    // "hard_link" does not follow symlinks, so linking to an earlier
+                    // symlink would copy it to a depth where it escapes "dst_dir"
+                    if !fs::symlink_metadata(&target_path)?.is_file() {
+                        return Err(invalid(format!(
+                            "hard link {} to {} is not to a regular file",
+                            entry_path.display(),
+                            target.display()
+                        )));
+                    }
+                    create_parent_dir(&output_path)?;
+                    fs::hard_link(target_path, &output_path)?;
+                }
+                // PAX and GNU long-name headers are consumed by the iterator
+                EntryType::XHeader | EntryType::XGlobalHeader => {}
+                other => {
+                    return Err(invalid(format!(
+                        "unsupported entry type {:?} for {}",
+                        other,
+                        entry_path.display()
+                    )))
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn unpack_zip(&mut self, archive_path: &Path) -> io::Result<()> {
+        let mut archive =
+            ZipArchive::new(self.cancel.reader(File::open(archive_path)?)).map_err(|e| {
+                invalid(format!(
+                    "failed to open zip {} ({})",
+                    archive_path.display(),
+                    e
+                ))
+            })?;
+        for i in 0..archive.len() {
+            let mut entry = archive
+                .by_index(i)
+                .map_err(|e| invalid(format!("failed to read zip entry {} ({})", i, e)))?;
+            self.count_entry()?;
+
+            // not "enclosed_name", which silently skips the escaping entries
+            let entry_path = PathBuf::from(entry.name());
+            let output_path = match self.output_path(&entry_path)? {
+                Some(v) => v,
+                None => continue,
+            };
+            let mode = entry.unix_mode();
+            if entry.is_dir() {
+                fs::create_dir_all(&output_path)?;
+            } else if mode.map(|m| m & 0o170000 == 0o120000).unwrap_or(false) {
+                let mut target = String::new();
+                entry.by_ref().take(4096).read_to_string(&mut target)?;
+                self.create_symlink(&entry_path, Path::new(&target), &output_path)?;
+            } else {
+                self.write_file(&mut entry, &output_path, mode.unwrap_or(0o755))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn count_entry(&mut self) -> io::Result<()> {
+        self.entries += 1;
+        if self.entries > self.limits.max_entries {
+            return Err(invalid(format!(
+                "archive has more than {} entries",
+                self.limits.max_entries
+            )));
+        }
+        Ok(())
+    }
+
+    /// Returns the path to unpack the entry to, or none for the root entry (e.g., "./").
+    /// Returns an error if the entry path is absolute or escapes the destination,
+    /// including through a symlink unpacked earlier.
+    fn output_path(&self, entry_path: &Path) -> io::Result<Option<PathBuf>> {
+        let escapes = || {
+            invalid(format!(
+                "entry {} escapes the destination directory",
+                entry_path.display()
+            ))
+        };
+
+        let mut components = Vec::new();
+        for component in entry_path.components() {
+            match component {
+                Component::Normal(v) => components.push(v),
+                Component::CurDir => {}
+                Component::ParentDir | Component::RootDir | Component::Prefix(_) => {
+                    return Err(escapes())
+                }
+            }
+        }
+        let last = match components.pop() {
+            Some(v) => v,
+            None => return Ok(None),
+        };
+
+        let mut output_path = self.dst_dir.to_path_buf();
+        for component in components {
+            output_path.push(component);
+            if let Ok(md) = fs::symlink_metadata(&output_path) {
+                if md.file_type().is_symlink() {
+                    return Err(escapes());
+                }
+            }
+        }
+        output_path.push(last);
+        Ok(Some(output_path))
+    }
+
+    /// Writes the entry to the file, counting its actual size,
+    /// since the sizes in the archive headers cannot be trusted.
+    fn write_file<R: Read>(&mut self, r: &mut R, output_path: &Path, mode: u32) -> io::Result<()> {
+        create_parent_dir(output_path)?;
+        // do not write through an existing symlink
+        if fs::symlink_metadata(output_path).is_ok() {
+            fs::remove_file(output_path)?;
+        }
+
+        let remaining = self.limits.max_total_bytes - self.total_bytes;
+        let mut f = File::create(output_path)?;
+        let written = io::copy(&mut r.take(remaining.saturating_add(1)), &mut f)?;
+        if written > remaining {
+            return Err(invalid(format!(
+                "archive unpacks to more than {} bytes",
+                self.limits.max_total_bytes
+            )));
+        }
+        self.total_bytes += written;
+
+        // no setuid, setgid, or sticky bits
+        f.set_permissions(PermissionsExt::from_mode(mode & 0o777))?;
+        Ok(())
+    }
+
+    /// Creates the symlink, if its "target" is relative and resolves within the destination.
+    /// ".." is only allowed at the start of the target, so that the target cannot
+    /// go up through another symlink in the destination.
+    fn create_symlink(
+        &self,
+        entry_path: &Path,
+        target: &Path,
+        output_path: &Path,
+    ) -> io::Result<()> {
+        let escapes = || {
+            invalid(format!(
+                "symlink {} -> {} escapes the destination directory",
+                entry_path.display(),
+                target.display()
+            ))
+        };
+
+        // depth of the symlink's directory within the destination
+        let rel = output_path
+            .strip_prefix(self.dst_dir)
+            .map_err(|_| escapes())?;
+        let mut depth = rel.components().count() as i64 - 1;
+        let mut descended = false;
+        for component in target.components() {
+            match component {
+                Component::Normal(_) => {
+                    descended = true;
+                    depth += 1;
+                }
+                Component::CurDir => {}
+                Component::ParentDir if !descended => {
+                    depth -= 1;
+                    if depth < 0 {
+                        return Err(escapes());
+                    }
+                }
+                Component::ParentDir | Component::RootDir | Component::Prefix(_) => {
+                    return Err(escapes())
+                }
+            }
+        }
+        if target.as_os_str().is_empty() {
+            return Err(escapes());
+        }
+
+        create_parent_dir(output_path)?;
+        if fs::symlink_metadata(output_path).is_ok() {
+            fs::remove_file(output_path)?;
+        }
+        symlink(target, output_path)
+    }
+}
+
+fn create_parent_dir(path: &Path) -> io::Result<()> {
+    if let Some(parent_dir) = path.parent() {
+        fs::create_dir_all(parent_dir)?;
+    }
+    Ok(())
+}
+
+fn invalid(msg: String) -> Error {
+    Error::new(ErrorKind::InvalidData, msg)
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-installer --lib -- extract::test_unpack_tar_gz --exact --show-output
+#[test]
+fn test_unpack_tar_gz() {
+    use flate2::{write::GzEncoder, Compression};
+    use tar::{Builder, Header};
+
+    let _ = env_logger::builder()
+        .filter_level(log::LevelFilter::Info)
+        .is_test(true)
+        .try_init();
+
+    // writes the raw names, since "tar::Builder" rejects the malicious ones
+    let tar_gz = |entries: &[(&str, EntryType, &str, &[u8])]| {
+        let archive_path = random_manager::tmp_path(10, Some(".tar.gz")).unwrap();
+        let enc = GzEncoder::new(File::create(&archive_path).unwrap(), Compression::default());
+        let mut builder = Builder::new(enc);
+        for (name, entry_type, link_name, data) in entries {
+            let mut header = Header::new_gnu();
+            header.as_old_mut().name[..name.len()].copy_from_slice(name.as_bytes());
+            header.set_entry_type(*entry_type);
+            header.set_link_name_literal(link_name).unwrap();
+            header.set_mode(0o755);
+            header.set_size(data.len() as u64);
+            header.set_cksum();
+            builder.append(&header, *data).unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap();
+        archive_path
+    };
+    let unpack_to = |archive_path: &str, limits: &Limits| {
+        let root_dir = random_manager::tmp_path(10, None).unwrap();
+        let dst_dir = Path::new(&root_dir).join("unpacked");
+        let res = unpack(archive_path, &dst_dir, ArchiveKind::TarGz, limits);
+        let outside: Vec<_> = fs::read_dir(&root_dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name())
+            .filter(|name| name != "unpacked")
+            .collect();
+        assert!(outside.is_empty(), "unpacked outside {:?}", outside);
+        (res, root_dir)
+    };
+
+    let archive_path = tar_gz(&[
+        ("./", EntryType::Directory, "", b""),
+        ("build/", EntryType::Directory, "", b""),
+        ("build/avalanchego", EntryType::Regular, "", b"avalanchego"),
+        (
+            "build/plugins/latest",
+            EntryType::Symlink,
+            "../avalanchego",
+            b"",
+        ),
+        ("build/copy", EntryType::Link, "build/avalanchego", b""),
+    ]);
+    let (res, root_dir) = unpack_to(&archive_path, &Limits::default());
+    res.unwrap();
+    let build_dir = Path::new(&root_dir).join("unpacked").join("build");
+    assert_eq!(
+        fs::read(build_dir.join("avalanchego")).unwrap(),
+        b"avalanchego"
+    );
+    assert_eq!(
+        fs::read(build_dir.join("plugins").join("latest")).unwrap(),
+        b"avalanchego"
+    );
+    assert_eq!(fs::read(build_dir.join("copy")).unwrap(), b"avalanchego");
+    let mode = fs::metadata(build_dir.join("avalanchego"))
+        .unwrap()
+        .permissions()
+        .mode();
+    assert_eq!(mode & 0o777, 0o755);
+    fs::remove_dir_all(root_dir).unwrap();
+    fs::remove_file(archive_path).unwrap();
+
+    for entries in [
+        vec![("../evil", EntryType::Regular, "", &b"evil"[..])],
+        vec![("build/../../evil", EntryType::Regular, "", b"evil")],
+        vec![("/tmp/evil", EntryType::Regular, "", b"evil")],
+        vec![("evil", EntryType::Symlink, "/etc/passwd", b"")],
+        vec![("evil", EntryType::Symlink, "../evil", b"")],
+        vec![("build/evil", EntryType::Symlink, "../../evil", b"")],
+        vec![("build/evil", EntryType::Symlink, "a/../../../evil", b"")],
+        vec![("evil", EntryType::Link, "/etc/passwd", b"")],
+        vec![("evil", EntryType::Link, "../evil", b"")],
+        // hard link to an earlier symlink, which only stays inside where it is
+        vec![
+            ("a/b/c/link", EntryType::Symlink, "../../x", b""),
+            ("top", EntryType::Link, "a/b/c/link", b""),
+        ],
+        vec![
+            ("build", EntryType::Directory, "", b""),
+            ("evil", EntryType::Link, "build", b""),
+        ],
+        // writes through an earlier symlink
+        vec![
+            ("build/up", EntryType::Symlink, "..", b""),
+            ("build/up/evil", EntryType::Symlink, "..", b""),
+        ],
+        vec![
+            ("build/up", EntryType::Symlink, "..", b""),
+            ("build/up/up/evil", EntryType::Regular, "", b"evil"),
+        ],
+    ] {
+        let archive_path = tar_gz(&entries);
+        let (res, root_dir) = unpack_to(&archive_path, &Limits::default());
+        let err = res.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData, "{:?}", entries);
+        log::info!("rejected {:?} ({})", entries, err);
+        fs::remove_dir_all(root_dir).unwrap();
+        fs::remove_file(archive_path).unwrap();
+    }
+
+    // decompression bombs
+    let zeros = vec![0u8; 1024 * 1024];
+    let archive_path = tar_gz(&[
+        ("a", EntryType::Regular, "", &zeros),
+        ("b", EntryType::Regular, "", &zeros),
+    ]);
+    let limits = Limits {
+        max_total_bytes: 1024 * 1024 + 1,
+        max_entries: 10,
+    };
+    let (res, root_dir) = unpack_to(&archive_path, &limits);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::InvalidData);
+    fs::remove_dir_all(root_dir).unwrap();
+    let limits = Limits {
+        max_total_bytes: u64::MAX,
+        max_entries: 1,
+    };
+    let (res, root_dir) = unpack_to(&archive_path, &limits);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::InvalidData);
+    fs::remove_dir_all(root_dir).unwrap();
+    fs::remove_file(archive_path).unwrap();
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-installer --lib -- extract::test_unpack_zip --exact --show-output
+#[test]
+fn test_unpack_zip() {
+    use std::io::Write;
+    use zip::{write::FileOptions, ZipWriter};
+
+    let _ = env_logger::builder()
+        .filter_level(log::LevelFilter::Info)
+        .is_test(true)
+        .try_init();
+
+    let zip = |f: &dyn Fn(&mut ZipWriter<File>)| {
+        let archive_path = random_manager::tmp_path(10, Some(".zip")).unwrap();
+        let mut w = ZipWriter::new(File::create(&archive_path).unwrap());
+        f(&mut w);
+        w.finish().unwrap();
+        archive_path
+    };
+    let unpack_to = |archive_path: &str, limits: &Limits| {
+        let root_dir = random_manager::tmp_path(10, None).unwrap();
+        let dst_dir = Path::new(&root_dir).join("unpacked");
+        let res = unpack(archive_path, &dst_dir, ArchiveKind::Zip, limits);
+        assert_eq!(fs::read_dir(&root_dir).unwrap().count(), 1);
+        (res, root_dir)
+    };
+    let opts = FileOptions::default().unix_permissions(0o755);
+
+    let archive_path = zip(&|w| {
+        w.add_directory("build/", opts).unwrap();
+        w.start_file("build/avalanchego", opts).unwrap();
+        w.write_all(b"avalanchego").unwrap();
+        w.add_symlink("build/latest", "avalanchego", opts).unwrap();
+    });
+    let (res, root_dir) = unpack_to(&archive_path, &Limits::default());
+    res.unwrap();
+    let build_dir = Path::new(&root_dir).join("unpacked").join("build");
+    assert_eq!(
+        fs::read(build_dir.join("avalanchego")).unwrap(),
+        b"avalanchego"
+    );
+    assert!(fs::symlink_metadata(build_dir.join("latest"))
+        .unwrap()
+        .file_type()
+        .is_symlink());
+    assert_eq!(fs::read(build_dir.join("latest")).unwrap(), b"avalanchego");
+    fs::remove_dir_all(&root_dir).unwrap();
+
+    let cancel = CancelToken::new();
+    cancel.cancel();
+    let err = unpack_with_cancel(
+        &archive_path,
+        &root_dir,
+        ArchiveKind::Zip,
+        &Limits::default(),
+        &cancel,
+    )
+    .unwrap_err();
+    assert!(crate::cancel::is_cancelled(&err));
+    fs::remove_file(archive_path).unwrap();
+
+    for (name, symlink_target) in [
+        ("../evil", None),
+        ("build/../../evil", None),
+        ("/tmp/evil", None),
+        ("evil", Some("/etc/passwd")),
+        ("evil", Some("../evil")),
+    ] {
+        let archive_path = zip(&|w| match symlink_target {
+            Some(target) => w.add_symlink(name, target, opts).unwrap(),
+            None => {
+                w.start_file(name, opts).unwrap();
+                w.write_all(b"evil").unwrap();
+            }
+        });
+        let (res, root_dir) = unpack_to(&archive_path, &Limits::default());
+        let err = res.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData, "{}", name);
+        log::info!("rejected {} ({})", name, err);
+        fs::remove_dir_all(root_dir).unwrap();
+        fs::remove_file(archive_path).unwrap();
+    }
+
+    // 16 MiB of zeros compresses to a few KiB
+    let archive_path = zip(&|w| {
+        w.start_file("bomb", opts).unwrap();
+        w.write_all(&vec![0u8; 16 * 1024 * 1024]).unwrap();
+    });
+    assert!(fs::metadata(&archive_path).unwrap().len() < 1024 * 1024);
+    let limits = Limits {
+        max_total_bytes: 1024 * 1024,
+        max_entries: 10,
+    };
+    let (res, root_dir) = unpack_to(&archive_path, &limits);
+    assert_eq!(res.unwrap_err().kind(), ErrorKind::InvalidData);
+    fs::remove_dir_all(root_dir).unwrap();
+    fs::remove_file(archive_path).unwrap();
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-installer --lib -- extract::test_archive_kind --exact --show-output
+#[test]
+fn test_archive_kind() {
+    for (file_name, expected) in [
+        ("avalanchego-linux-amd64-v1.10.3.tar.gz", ArchiveKind::TarGz),
+        ("subnet-evm_0.5.1_darwin_arm64.tar.gz", ArchiveKind::TarGz),
+        ("avalanchego-macos-v1.10.3.zip", ArchiveKind::Zip),
+        (
+            "https://github.com/ava-labs/avalanchego/releases/download/v1.10.3/avalanchego-win-v1.10.3-experimental.zip",
+            ArchiveKind::Zip,
+        ),
+    ] {
+        let kind = ArchiveKind::from_file_name(file_name).unwrap();
+        assert_eq!(kind, expected, "{}", file_name);
+        assert!(file_name.ends_with(kind.suffix()));
+    }
+    for file_name in ["avalanchego", "avalanchego.tar", "avalanchego.tar.gz.sig"] {
+        let err = ArchiveKind::from_file_name(file_name).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput, "{}", file_name);
+    }
+}
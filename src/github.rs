@@ -3,8 +3,10 @@ use std::{
     time::Duration,
 };
 
-use reqwest::ClientBuilder;
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tokio::time::sleep;
+
+use crate::extract::ArchiveKind;
 
 /// ref. https://github.com/ava-labs/avalanchego/releases
 /// ref. https://api.github.com/repos/ava-labs/avalanchego/releases/latest
@@ -13,10 +15,83 @@ pub async fn fetch_latest_release(org: &str, repo: &str) -> io::Result<ReleaseRe
         "https://api.github.com/repos/{}/{}/releases/latest",
         org, repo
     );
+    fetch_release_response(&ep).await
+}
+
+/// ref. https://github.com/ava-labs/avalanchego/releases/tag/v1.10.3
+/// ref. https://api.github.com/repos/ava-labs/avalanchego/releases/tags/v1.10.3
+pub async fn fetch_release(org: &str, repo: &str, tag_name: &str) -> io::Result<ReleaseResponse> {
+    let ep = format!(
+        "https://api.github.com/repos/{}/{}/releases/tags/{}",
+        org, repo, tag_name
+    );
+    fetch_release_response(&ep).await
+}
+
+/// Fetches the release for the "tag_name", or the latest release if none,
+/// retrying on failures and on responses with missing fields.
+/// Returns the last response if the tag name never shows up.
+pub async fn fetch_release_with_retries(
+    org: &str,
+    repo: &str,
+    tag_name: Option<&str>,
+) -> io::Result<ReleaseResponse> {
+    let mut release_info = ReleaseResponse::default();
+    let mut last_err = None;
+    for round in 0..10 {
+        let fetched = if let Some(tag_name) = tag_name {
+            fetch_release(org, repo, tag_name).await
+        } else {
+            fetch_latest_release(org, repo).await
+        };
+        let info = match fetched {
+            Ok(v) => v,
+            Err(e) => {
+                log::warn!("failed to fetch release {} -- retrying {}...", e, round + 1);
+                last_err = Some(e);
+                sleep(Duration::from_secs((round + 1) * 3)).await;
+                continue;
+            }
+        };
+
+        release_info = info;
+        if release_info.tag_name.is_some() && release_info.assets.is_some() {
+            return Ok(release_info);
+        }
+
+        log::warn!(
+            "release_info.tag_name or assets is None -- retrying {}...",
+            round + 1
+        );
+        sleep(Duration::from_secs((round + 1) * 3)).await;
+    }
+
+    if release_info.tag_name.is_none() {
+        if let Some(e) = last_err {
+            return Err(e);
+        }
+    }
+    Ok(release_info)
+}
+
+/// Fetches the most recent releases, newest first, including prereleases.
+/// ref. https://api.github.com/repos/ava-labs/avalanchego/releases
+pub async fn fetch_releases(org: &str, repo: &str) -> io::Result<Vec<ReleaseResponse>> {
+    let ep = format!(
+        "https://api.github.com/repos/{}/{}/releases?per_page=100",
+        org, repo
+    );
+    fetch_json(&ep).await
+}
+
+async fn fetch_release_response(ep: &str) -> io::Result<ReleaseResponse> {
+    fetch_json(ep).await
+}
+
+async fn fetch_json<T: DeserializeOwned>(ep: &str) -> io::Result<T> {
     log::info!("fetching {}", ep);
 
-    let cli = ClientBuilder::new()
-        .user_agent(env!("CARGO_PKG_NAME"))
+    let cli = crate::download::client_builder()
         .danger_accept_invalid_certs(true)
         .timeout(Duration::from_secs(15))
         .connection_verbose(true)
@@ -28,7 +103,7 @@ pub async fn fetch_latest_release(org: &str, repo: &str) -> io::Result<ReleaseRe
             )
         })?;
     let resp =
-        cli.get(&ep).send().await.map_err(|e| {
+        cli.get(ep).send().await.map_err(|e| {
             Error::new(ErrorKind::Other, format!("failed ClientBuilder send {}", e))
         })?;
     let out = resp
@@ -37,7 +112,7 @@ pub async fn fetch_latest_release(org: &str, repo: &str) -> io::Result<ReleaseRe
         .map_err(|e| Error::new(ErrorKind::Other, format!("failed ClientBuilder send {}", e)))?;
     let out: Vec<u8> = out.into();
 
-    let resp: ReleaseResponse = match serde_json::from_slice(&out) {
+    let resp: T = match serde_json::from_slice(&out) {
         Ok(p) => p,
         Err(e) => {
             return Err(Error::new(
@@ -50,7 +125,7 @@ pub async fn fetch_latest_release(org: &str, repo: &str) -> io::Result<ReleaseRe
 }
 
 /// ref. https://api.github.com/repos/ava-labs/avalanchego/releases/latest
-#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Default)]
 #[serde(rename_all = "snake_case")]
 pub struct ReleaseResponse {
     /// Sometimes empty for github API consistency issue.
@@ -60,21 +135,70 @@ pub struct ReleaseResponse {
 
     #[serde(default)]
     pub prerelease: bool,
-}
 
-impl Default for ReleaseResponse {
-    fn default() -> Self {
-        Self::default()
-    }
+    /// Release page URL (e.g., "https://github.com/ava-labs/avalanchego/releases/tag/v1.10.3").
+    #[serde(default)]
+    pub html_url: Option<String>,
+    /// Release notes in markdown.
+    #[serde(default)]
+    pub body: Option<String>,
 }
 
 impl ReleaseResponse {
-    pub fn default() -> Self {
-        Self {
-            tag_name: None,
-            assets: None,
-            prerelease: false,
+    /// Returns the names of all assets in the release.
+    pub fn asset_names(&self) -> Vec<String> {
+        self.assets
+            .as_ref()
+            .map(|assets| assets.iter().map(|a| a.name.clone()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Selects the release asset that matches the query, and returns it
+    /// with the decoder for its archive type.
+    ///
+    /// Assets that name one of the requested arch tokens are preferred over
+    /// assets that name no arch at all (e.g., "avalanchego-macos-v1.10.3.zip"),
+    /// which are only selected if the query allows them.
+    /// Assets that name any other arch are never selected.
+    /// The error lists all available assets when nothing matches.
+    pub fn select_asset(&self, query: &AssetQuery) -> io::Result<(Asset, ArchiveKind)> {
+        let mut arch_agnostic = None;
+        for asset in self.assets.iter().flatten() {
+            if !asset.name.starts_with(query.prefix) {
+                continue;
+            }
+            let archive_kind = match ArchiveKind::from_file_name(&asset.name) {
+                Ok(v) => v,
+                Err(_) => continue,
+            };
+            if !asset.has_any_token(query.os_tokens) {
+                continue;
+            }
+            if asset.has_any_token(query.arch_tokens) {
+                return Ok((asset.clone(), archive_kind));
+            }
+            if query.allow_arch_agnostic
+                && !asset.has_any_token(query.known_arch_tokens)
+                && arch_agnostic.is_none()
+            {
+                arch_agnostic = Some((asset.clone(), archive_kind));
+            }
+        }
+        if let Some(v) = arch_agnostic {
+            return Ok(v);
         }
+
+        Err(Error::new(
+            ErrorKind::NotFound,
+            format!(
+                "no release asset matching '{}' (os {:?}, arch {:?}) for tag {}; available assets: {:?}",
+                query.prefix,
+                query.os_tokens,
+                query.arch_tokens,
+                self.tag_name.clone().unwrap_or_default(),
+                self.asset_names()
+            ),
+        ))
     }
 }
 
@@ -84,4 +208,37 @@ impl ReleaseResponse {
 pub struct Asset {
     pub name: String,
     pub browser_download_url: String,
+    /// Size of the asset in bytes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+}
+
+impl Asset {
+    /// Returns true if the asset name contains any of the tokens,
+    /// where the name is split by "-" and "_" after removing its archive extension.
+    /// e.g., "avalanchego-linux-amd64-v1.10.3.tar.gz" has "linux" and "amd64".
+    /// e.g., "subnet-evm_0.5.1_darwin_arm64.tar.gz" has "darwin" and "arm64".
+    pub fn has_any_token(&self, tokens: &[&str]) -> bool {
+        let name = self
+            .name
+            .trim_end_matches(".tar.gz")
+            .trim_end_matches(".zip");
+        name.split(['-', '_'])
+            .any(|t| tokens.iter().any(|v| t.eq_ignore_ascii_case(v)))
+    }
+}
+
+/// Represents the criteria to select a release asset.
+#[derive(Debug, Clone)]
+pub struct AssetQuery<'a> {
+    /// Asset name prefix (e.g., "avalanchego", "subnet-evm").
+    pub prefix: &'a str,
+    /// Any of these must appear in the asset name (e.g., "macos", "darwin").
+    pub os_tokens: &'a [&'a str],
+    /// Any of these is preferred in the asset name (e.g., "arm64", "aarch64").
+    pub arch_tokens: &'a [&'a str],
+    /// All arch tokens, to reject assets built for other arches.
+    pub known_arch_tokens: &'a [&'a str],
+    /// Set true to fall back to an asset that names no arch.
+    pub allow_arch_agnostic: bool,
 }
@@ -0,0 +1,843 @@
+use std::{
+    fs::{self, File},
+    io::{self, Error, ErrorKind, Write},
+    os::unix::fs::symlink,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    cancel::CancelToken,
+    disk,
+    download::{download_file_with_preflight, new_client_with_timeouts, DownloadedFile, Timeouts},
+    extract::Limits,
+    platform::{Arch, Os, Platform},
+    project::Project,
+    version::VersionReport,
+};
+
+/// Represents the options to download and install a release.
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+    /// Leave empty to auto-detect from the local system.
+    pub arch: Option<Arch>,
+    /// Leave empty to auto-detect from the local system.
+    pub os: Option<Os>,
+    /// Leave empty to download the latest release.
+    pub release_tag: Option<String>,
+    /// Set true to run the installed binary with "--version",
+    /// and fail if it does not report the resolved release tag.
+    /// Skipped if the binary is not built for the local platform.
+    pub verify_version: bool,
+    /// Set to pin the release assets in the lockfile (e.g., "avalanche-installer.lock").
+    /// The pinned asset is installed if the lockfile has one for the platform,
+    /// and the installed asset is pinned otherwise, once the install succeeded.
+    /// See "lock::Lockfile".
+    pub lock_file: Option<PathBuf>,
+    /// Set to share the HTTP client across downloads (see "download::new_client").
+    /// Leave empty to create one per download.
+    pub client: Option<reqwest::Client>,
+    /// Timeouts for downloading the release assets.
+    /// The "connect" timeout is ignored if "client" is set,
+    /// since it is configured on the client. See "download::Timeouts".
+    pub timeouts: Timeouts,
+    /// Directory to create the temporary workspaces under, for the downloaded
+    /// archives and the unpacked files. Leave empty to use the system
+    /// temporary directory. See "workspace::Workspace".
+    pub workspace_root: Option<PathBuf>,
+    /// Set true to keep the workspace after the install, for debugging.
+    pub keep_workspace: bool,
+    /// Directory to relocate the workspace to if "workspace_root" is mounted
+    /// "noexec", when the downloaded binary is returned from the workspace.
+    /// Leave empty to fail instead. See "workspace::Workspace::new_executable".
+    pub fallback_workspace_root: Option<PathBuf>,
+    /// Limits on the unpacked release archive. See "extract::Limits".
+    pub unpack_limits: Limits,
+    /// Set true to skip checking the free disk space before downloading.
+    /// See "disk::preflight".
+    pub skip_disk_check: bool,
+    /// Cancels the install from another task (see "cancel::CancelToken").
+    pub cancel: CancelToken,
+}
+
+/// Represents the result of installing a release binary.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct InstallOutcome {
+    pub project: Project,
+    /// Path to the installed binary.
+    pub binary_path: PathBuf,
+    /// Resolved release tag (e.g., "v1.10.3").
+    pub tag_name: String,
+    pub platform: Platform,
+    /// URL of the downloaded release asset.
+    pub source_url: String,
+    /// Hex-encoded SHA-256 digest of the downloaded archive.
+    pub archive_sha256: String,
+    pub bytes_downloaded: u64,
+    /// True if the binary was already installed and nothing was downloaded.
+    pub cache_hit: bool,
+    pub elapsed: Duration,
+    /// Version reported by the installed binary, if verified.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<VersionReport>,
+}
+
+impl InstallOutcome {
+    /// Runs the installed binary with "--version", and returns an error
+    /// if it does not report the resolved release tag.
+    /// Skipped if the binary is not built for the local platform.
+    pub fn verify_version(&mut self) -> io::Result<()> {
+        if Platform::detect().ok() != Some(self.platform) {
+            log::warn!(
+                "skipping version verification for {} binary {}",
+                self.platform,
+                self.binary_path.display()
+            );
+            return Ok(());
+        }
+
+        let report = VersionReport::from_binary(&self.binary_path)?;
+        report.ensure_matches_tag(&self.tag_name)?;
+        log::info!(
+            "verified {} reports version {}",
+            self.binary_path.display(),
+            report.version
+        );
+        self.version = Some(report);
+        Ok(())
+    }
+
+    /// Same as "verify_version" but runs the binary on the blocking thread pool.
+    #[cfg_attr(
+        not(any(feature = "avalanchego", feature = "subnet_evm")),
+        allow(dead_code)
+    )]
+    pub(crate) async fn verify_version_blocking(mut self) -> io::Result<Self> {
+        crate::version::run_blocking(move || {
+            self.verify_version()?;
+            Ok(self)
+        })
+        .await
+    }
+}
+
+/// Downloads the release archive from the "source_url" to the "file_path"
+/// in the "workspace_dir", with the shared HTTP client if set, and the "timeouts".
+/// Unless "skip_disk_check" is set, fails before writing anything if the
+/// workspace and "target_dir" filesystems do not have the space to download
+/// and unpack the archive, sized by the response "Content-Length",
+/// or by the "expected_bytes" from the release metadata if none.
+#[cfg_attr(
+    not(any(feature = "avalanchego", feature = "subnet_evm")),
+    allow(dead_code)
+)]
+pub(crate) async fn download_archive(
+    opts: &Options,
+    source_url: &str,
+    file_path: &str,
+    expected_bytes: Option<u64>,
+    workspace_dir: &Path,
+    target_dir: Option<&Path>,
+) -> io::Result<DownloadedFile> {
+    let preflight = |content_length: Option<u64>| {
+        if opts.skip_disk_check {
+            return Ok(());
+        }
+        match content_length.or(expected_bytes) {
+            Some(archive_bytes) => disk::preflight(
+                archive_bytes,
+                workspace_dir,
+                target_dir,
+                &opts.unpack_limits,
+            ),
+            None => {
+                log::warn!("unknown size of {}, skipping disk space check", source_url);
+                Ok(())
+            }
+        }
+    };
+    let cli = match &opts.client {
+        Some(cli) => cli.clone(),
+        None => new_client_with_timeouts(&opts.timeouts)?,
+    };
+    opts.cancel
+        .run(download_file_with_preflight(
+            &cli,
+            source_url,
+            file_path,
+            &opts.timeouts,
+            preflight,
+        ))
+        .await
+}
+
+/// Recursively copies the "src" directory into the "dst" directory,
+/// creating it if missing, and overwriting the existing files with the same names.
+/// File permissions are preserved, and each file is replaced atomically
+/// (see "copy_file_atomic"). Symlinks are recreated as they are,
+/// never followed, so their targets are not copied in.
+pub fn copy_dir_all<P: AsRef<Path>, Q: AsRef<Path>>(src: P, dst: Q) -> io::Result<()> {
+    fs::create_dir_all(&dst)?;
+    for entry in fs::read_dir(&src)? {
+        let entry = entry?;
+        let dst_path = dst.as_ref().join(entry.file_name());
+        let file_type = entry.file_type()?;
+        if file_type.is_symlink() {
+            copy_symlink_atomic(&entry.path(), &dst_path)?;
+        } else if file_type.is_dir() {
+            copy_dir_all(entry.path(), &dst_path)?;
+        } else {
+            copy_file_atomic(entry.path(), &dst_path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Recreates the "src" symlink at the "dst" path with the same target,
+/// replacing "dst" atomically.
+fn copy_symlink_atomic(src: &Path, dst: &Path) -> io::Result<()> {
+    let target = fs::read_link(src)?;
+    let (_, tmp_path) = sibling_tmp_path(dst)?;
+    log::info!("linking {} to {}", dst.display(), target.display());
+    symlink(&target, &tmp_path)?;
+    if let Err(e) = fs::rename(&tmp_path, dst) {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// Copies the "src" file to the "dst" path, so that "dst" is either the old
+/// or the complete new file, even if the process crashes mid-copy.
+/// The file is written to a temporary file in the "dst" directory, synced,
+/// given the permissions of "src", and then renamed over "dst".
+///
+/// A running binary can be replaced this way, since the rename only swaps
+/// the directory entry. The running process keeps its copy,
+/// and the new binary is picked up on the next start.
+pub fn copy_file_atomic<P: AsRef<Path>, Q: AsRef<Path>>(src: P, dst: Q) -> io::Result<()> {
+    let (src, dst) = (src.as_ref(), dst.as_ref());
+    let (parent_dir, tmp_path) = sibling_tmp_path(dst)?;
+
+    log::info!("copying {} to {}", src.display(), dst.display());
+    let copied = (|| {
+        let mut src_file = File::open(src)?;
+        let mut tmp_file = File::create(&tmp_path)?;
+        io::copy(&mut src_file, &mut tmp_file)?;
+        tmp_file.sync_all()?;
+        tmp_file.set_permissions(src_file.metadata()?.permissions())?;
+        fs::rename(&tmp_path, dst)
+    })();
+    if let Err(e) = copied {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(e);
+    }
+
+    // persist the rename itself
+    File::open(parent_dir)?.sync_all()
+}
+
+/// Writes the data to the "file_path", so that the file is either the old
+/// or the complete new one, even if the process crashes mid-write.
+/// Same as "copy_file_atomic" but from memory.
+pub fn write_file_atomic<P: AsRef<Path>>(file_path: P, d: &[u8]) -> io::Result<()> {
+    let file_path = file_path.as_ref();
+    let (parent_dir, tmp_path) = sibling_tmp_path(file_path)?;
+
+    let written = (|| {
+        let mut tmp_file = File::create(&tmp_path)?;
+        tmp_file.write_all(d)?;
+        tmp_file.sync_all()?;
+        fs::rename(&tmp_path, file_path)
+    })();
+    if let Err(e) = written {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(e);
+    }
+    File::open(parent_dir)?.sync_all()
+}
+
+/// Returns the parent directory of the "dst" path, and a temporary file path
+/// in it, so that the temporary file can be renamed over "dst".
+fn sibling_tmp_path(dst: &Path) -> io::Result<(&Path, PathBuf)> {
+    let file_name = dst.file_name().ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("invalid destination path {}", dst.display()),
+        )
+    })?;
+    let parent_dir = match dst.parent() {
+        Some(v) if !v.as_os_str().is_empty() => v,
+        _ => Path::new("."),
+    };
+    let tmp_path = parent_dir.join(format!(
+        ".{}.{}.tmp",
+        file_name.to_string_lossy(),
+        random_manager::secure_string(10)
+    ));
+    Ok((parent_dir, tmp_path))
+}
+
+/// Returns the hex-encoded SHA-256 digest of the file.
+pub fn sha256_file<P: AsRef<Path>>(file_path: P) -> io::Result<String> {
+    let mut f = File::open(file_path)?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut f, &mut hasher)?;
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Name of the directory next to the installed binaries where the installer
+/// keeps its records. A directory is used instead of a sidecar file,
+/// since avalanchego treats every file in the plugin directory as a VM plugin.
+pub const RECORDS_DIR_NAME: &str = ".installer";
+
+/// Represents the receipt recorded next to an installed binary, to tell
+/// where the binary came from, and to skip re-installing the same release.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct InstallReceipt {
+    pub project: Project,
+    /// Installed release tag (e.g., "v1.10.3").
+    pub tag_name: String,
+    pub platform: Platform,
+    /// URL of the downloaded release asset.
+    pub source_url: String,
+    /// Hex-encoded SHA-256 digest of the downloaded archive.
+    pub archive_sha256: String,
+    /// Hex-encoded SHA-256 digest of the installed binary.
+    pub binary_sha256: String,
+    /// Unix timestamp in seconds of the install.
+    pub installed_at: u64,
+    /// Version of this installer crate that installed the binary.
+    pub installer_version: String,
+    /// Version reported by the binary with "--version" at install,
+    /// or none if not verified.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<VersionReport>,
+}
+
+impl InstallReceipt {
+    /// Returns the receipt file path for the binary (e.g.,
+    /// "/data/bin/.installer/avalanchego.install-receipt.json" for "/data/bin/avalanchego").
+    pub fn path_for<P: AsRef<Path>>(binary_path: P) -> PathBuf {
+        let binary_path = binary_path.as_ref();
+        let file_name = binary_path
+            .file_name()
+            .map(|v| v.to_string_lossy().to_string())
+            .unwrap_or_default();
+        binary_path
+            .parent()
+            .unwrap_or_else(|| Path::new(""))
+            .join(RECORDS_DIR_NAME)
+            .join(format!("{}.install-receipt.json", file_name))
+    }
+
+    /// Records the receipt of the installed binary.
+    pub fn record(outcome: &InstallOutcome) -> io::Result<Self> {
+        let installed_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let receipt = Self {
+            project: outcome.project,
+            tag_name: outcome.tag_name.clone(),
+            platform: outcome.platform,
+            source_url: outcome.source_url.clone(),
+            archive_sha256: outcome.archive_sha256.clone(),
+            binary_sha256: sha256_file(&outcome.binary_path)?,
+            installed_at,
+            installer_version: env!("CARGO_PKG_VERSION").to_string(),
+            version: outcome.version.clone(),
+        };
+        receipt.save(&outcome.binary_path)?;
+        Ok(receipt)
+    }
+
+    /// Saves the receipt for the binary atomically, so that a crash
+    /// never leaves a half-written receipt behind.
+    pub fn save<P: AsRef<Path>>(&self, binary_path: P) -> io::Result<()> {
+        let file_path = Self::path_for(binary_path);
+        log::info!("saving install receipt to {}", file_path.display());
+        if let Some(parent_dir) = file_path.parent() {
+            fs::create_dir_all(parent_dir)?;
+        }
+        let d = serde_json::to_vec_pretty(self).map_err(|e| {
+            Error::new(
+                ErrorKind::Other,
+                format!("failed to serialize install receipt {}", e),
+            )
+        })?;
+        write_file_atomic(file_path, &d)
+    }
+
+    /// Loads the receipt for the binary, returning none if not recorded.
+    pub fn load<P: AsRef<Path>>(binary_path: P) -> io::Result<Option<Self>> {
+        let file_path = Self::path_for(binary_path);
+        match fs::metadata(&file_path) {
+            Ok(_) => Self::load_file(file_path).map(Some),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Loads the receipt from the receipt file.
+    pub fn load_file<P: AsRef<Path>>(file_path: P) -> io::Result<Self> {
+        let file_path = file_path.as_ref();
+        let d = fs::read(file_path)?;
+        serde_json::from_slice(&d).map_err(|e| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "failed to decode install receipt {} ({})",
+                    file_path.display(),
+                    e
+                ),
+            )
+        })
+    }
+}
+
+/// Checks whether the release "tag_name" for the "platform" is already installed
+/// at the "binary_path". Returns the cache-hit install outcome if so,
+/// or none if the binary is missing, different, or corrupted.
+///
+/// The binary matches if its recorded receipt has the same tag, platform and
+/// binary digest. Without the recorded receipt, a binary for the local platform
+/// matches if it reports the same version with "--version". No receipt is
+/// recorded then, since the asset the binary came from is unknown.
+pub fn find_installed<P: AsRef<Path>>(
+    binary_path: P,
+    project: Project,
+    tag_name: &str,
+    platform: Platform,
+) -> io::Result<Option<InstallOutcome>> {
+    let binary_path = binary_path.as_ref();
+    if !binary_path.exists() {
+        log::info!("{} not found", binary_path.display());
+        return Ok(None);
+    }
+    let cache_hit = |source_url: String, archive_sha256: String| InstallOutcome {
+        project,
+        binary_path: binary_path.to_path_buf(),
+        tag_name: tag_name.to_string(),
+        platform,
+        source_url,
+        archive_sha256,
+        bytes_downloaded: 0,
+        cache_hit: true,
+        elapsed: Duration::default(),
+        version: None,
+    };
+
+    match InstallReceipt::load(binary_path) {
+        Ok(Some(receipt)) => {
+            if receipt.tag_name != tag_name || receipt.platform != platform {
+                log::info!(
+                    "{} has {} for {}, want {} for {}",
+                    binary_path.display(),
+                    receipt.tag_name,
+                    receipt.platform,
+                    tag_name,
+                    platform
+                );
+                return Ok(None);
+            }
+            let binary_sha256 = sha256_file(binary_path)?;
+            if binary_sha256 != receipt.binary_sha256 {
+                log::warn!(
+                    "{} has sha256 {}, recorded {} -- reinstalling",
+                    binary_path.display(),
+                    binary_sha256,
+                    receipt.binary_sha256
+                );
+                return Ok(None);
+            }
+            log::info!(
+                "{} already has {} for {}",
+                binary_path.display(),
+                tag_name,
+                platform
+            );
+            return Ok(Some(cache_hit(receipt.source_url, receipt.archive_sha256)));
+        }
+        Ok(None) => {}
+        Err(e) => {
+            log::warn!("ignoring invalid install receipt ({})", e);
+            return Ok(None);
+        }
+    }
+
+    if Platform::detect().ok() != Some(platform) {
+        return Ok(None);
+    }
+    match VersionReport::from_binary(binary_path) {
+        Ok(report) if report.matches_tag(tag_name) => {
+            log::info!(
+                "{} reports version {} without install receipt",
+                binary_path.display(),
+                report.version
+            );
+            let mut outcome = cache_hit(String::new(), String::new());
+            outcome.version = Some(report);
+            Ok(Some(outcome))
+        }
+        Ok(report) => {
+            log::info!(
+                "{} reports version {}, want {}",
+                binary_path.display(),
+                report.version,
+                tag_name
+            );
+            Ok(None)
+        }
+        Err(e) => {
+            log::warn!("failed to check {} version ({})", binary_path.display(), e);
+            Ok(None)
+        }
+    }
+}
+
+/// Represents the result of verifying an installed binary
+/// against its recorded install receipt.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct Verification {
+    pub binary_path: PathBuf,
+    /// Recorded release tag (e.g., "v1.10.3").
+    pub tag_name: String,
+    pub platform: Platform,
+    /// True if the binary digest matches the recorded one.
+    pub sha256_matches: bool,
+    /// True if the binary reports the recorded release tag with "--version",
+    /// or none if skipped since the binary is not built for the local platform.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version_matches: Option<bool>,
+    /// Version reported by the binary, if run.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<VersionReport>,
+}
+
+impl Verification {
+    /// Returns true if all checks passed.
+    pub fn is_ok(&self) -> bool {
+        self.sha256_matches && self.version_matches != Some(false)
+    }
+}
+
+/// Verifies the installed binary against its recorded install receipt.
+/// Returns an error if the binary or its receipt is missing.
+pub fn verify_installed<P: AsRef<Path>>(binary_path: P) -> io::Result<Verification> {
+    let binary_path = binary_path.as_ref();
+    let receipt = InstallReceipt::load(binary_path)?.ok_or_else(|| {
+        Error::new(
+            ErrorKind::NotFound,
+            format!("no install receipt for {}", binary_path.display()),
+        )
+    })?;
+
+    let binary_sha256 = sha256_file(binary_path)?;
+    let mut verification = Verification {
+        binary_path: binary_path.to_path_buf(),
+        tag_name: receipt.tag_name.clone(),
+        platform: receipt.platform,
+        sha256_matches: binary_sha256 == receipt.binary_sha256,
+        version_matches: None,
+        version: None,
+    };
+    if !verification.sha256_matches {
+        log::warn!(
+            "{} has sha256 {}, recorded {}",
+            binary_path.display(),
+            binary_sha256,
+            receipt.binary_sha256
+        );
+    }
+
+    if Platform::detect().ok() == Some(receipt.platform) {
+        let report = VersionReport::from_binary(binary_path)?;
+        verification.version_matches = Some(report.matches_tag(&receipt.tag_name));
+        verification.version = Some(report);
+    }
+    Ok(verification)
+}
+
+/// Records the install receipt for the existing "binary_path", as if the
+/// release was installed there, so that the tests download nothing.
+#[cfg(test)]
+pub(crate) fn record_fake_install(
+    project: Project,
+    binary_path: &Path,
+    tag_name: &str,
+    platform: Platform,
+) -> InstallOutcome {
+    let outcome = InstallOutcome {
+        project,
+        binary_path: binary_path.to_path_buf(),
+        tag_name: tag_name.to_string(),
+        platform,
+        source_url: String::new(),
+        archive_sha256: String::new(),
+        bytes_downloaded: 0,
+        cache_hit: false,
+        elapsed: Duration::default(),
+        version: None,
+    };
+    InstallReceipt::record(&outcome).unwrap();
+    outcome
+}
+
+/// Represents the fake "subnet-evm" release, served over HTTP and pinned in
+/// the lockfile, so that the tests download nothing from GitHub.
+/// Every request is served from its own thread, outside of any async runtime.
+#[cfg(all(test, feature = "subnet_evm"))]
+pub(crate) struct FakeRelease {
+    pub tag_name: String,
+    /// Release archive with the shell script that reports the tag version
+    /// in place of the binary.
+    pub archive: Vec<u8>,
+    /// Delay before each response.
+    pub delay: Duration,
+    /// Set to stall the response after this many bytes of the archive.
+    pub stall_after: Option<usize>,
+}
+
+#[cfg(all(test, feature = "subnet_evm"))]
+impl FakeRelease {
+    pub(crate) fn new(tag_name: &str) -> Self {
+        use flate2::{write::GzEncoder, Compression};
+
+        let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+        let data = format!(
+            "#!/bin/sh\necho 'Subnet-EVM/{} [rpcchainvm=26]'\n",
+            tag_name
+        );
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o755);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "subnet-evm", data.as_bytes())
+            .unwrap();
+        Self {
+            tag_name: tag_name.to_string(),
+            archive: builder.into_inner().unwrap().finish().unwrap(),
+            delay: Duration::default(),
+            stall_after: None,
+        }
+    }
+
+    /// Serves the archive on a local port, and pins it for the platform
+    /// in the "lock_file". Returns the most requests in flight at once.
+    pub(crate) fn serve(
+        self,
+        lock_file: &Path,
+        platform: Platform,
+    ) -> std::sync::Arc<std::sync::atomic::AtomicUsize> {
+        use std::{
+            io::Read,
+            net::TcpListener,
+            sync::{
+                atomic::{AtomicUsize, Ordering},
+                Arc,
+            },
+            thread,
+        };
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let source_url = format!(
+            "http://{}/subnet-evm_{}_{}.tar.gz",
+            listener.local_addr().unwrap(),
+            platform.os,
+            platform.arch
+        );
+        let mut lockfile = crate::lock::Lockfile::default();
+        lockfile.insert(crate::lock::Pin {
+            project: Project::SubnetEvm,
+            platform,
+            tag_name: self.tag_name.clone(),
+            source_url,
+            sha256: hex::encode(Sha256::digest(&self.archive)),
+        });
+        lockfile.sync(lock_file).unwrap();
+
+        let release = Arc::new(self);
+        let (in_flight, max_in_flight) =
+            (Arc::new(AtomicUsize::new(0)), Arc::new(AtomicUsize::new(0)));
+        let counted = max_in_flight.clone();
+        thread::spawn(move || {
+            for conn in listener.incoming() {
+                let mut conn = conn.unwrap();
+                let (release, in_flight, max_in_flight) =
+                    (release.clone(), in_flight.clone(), counted.clone());
+                thread::spawn(move || {
+                    let mut req = Vec::new();
+                    let mut buf = [0u8; 1024];
+                    while !req.windows(4).any(|w| w == b"\r\n\r\n") {
+                        match conn.read(&mut buf) {
+                            Ok(0) | Err(_) => return,
+                            Ok(n) => req.extend_from_slice(&buf[..n]),
+                        }
+                    }
+                    let n = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                    max_in_flight.fetch_max(n, Ordering::SeqCst);
+                    thread::sleep(release.delay);
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+
+                    let header = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                        release.archive.len()
+                    );
+                    let _ = conn.write_all(header.as_bytes());
+                    match release.stall_after {
+                        Some(n) => {
+                            let _ = conn.write_all(&release.archive[..n]);
+                            thread::sleep(Duration::from_secs(60));
+                        }
+                        None => {
+                            let _ = conn.write_all(&release.archive);
+                        }
+                    }
+                });
+            }
+        });
+        max_in_flight
+    }
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-installer --lib -- install::test_install_receipt --exact --show-output
+#[test]
+fn test_install_receipt() {
+    let dir = Path::new(&random_manager::tmp_path(10, None).unwrap()).to_path_buf();
+    fs::create_dir_all(&dir).unwrap();
+    let binary_path = dir.join("avalanchego");
+    fs::write(&binary_path, b"not a real binary").unwrap();
+    assert!(InstallReceipt::load(&binary_path).unwrap().is_none());
+
+    // recorded for another platform, so verification skips "--version"
+    let platform = match Platform::detect() {
+        Ok(Platform {
+            os: Os::Linux,
+            arch: Arch::Amd64,
+        }) => Platform::new(Os::Linux, Arch::Arm64),
+        _ => Platform::new(Os::Linux, Arch::Amd64),
+    };
+    record_fake_install(Project::AvalancheGo, &binary_path, "v1.10.3", platform);
+    let receipt = InstallReceipt::load(&binary_path).unwrap().unwrap();
+    assert_eq!(receipt.project, Project::AvalancheGo);
+    assert_eq!(receipt.installer_version, env!("CARGO_PKG_VERSION"));
+    assert_eq!(receipt.binary_sha256, sha256_file(&binary_path).unwrap());
+    assert!(InstallReceipt::path_for(&binary_path)
+        .ends_with(".installer/avalanchego.install-receipt.json"));
+    assert_eq!(
+        InstallReceipt::load(&binary_path).unwrap(),
+        Some(receipt.clone())
+    );
+
+    let verification = verify_installed(&binary_path).unwrap();
+    assert!(verification.is_ok());
+    assert_eq!(verification.version_matches, None);
+
+    fs::write(&binary_path, b"tampered").unwrap();
+    assert!(!verify_installed(&binary_path).unwrap().is_ok());
+    assert!(
+        find_installed(&binary_path, Project::AvalancheGo, "v1.10.3", platform)
+            .unwrap()
+            .is_none()
+    );
+
+    // found by its "--version" without the receipt, which is not recorded
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        let binary_path = dir.join("unrecorded");
+        fs::write(
+            &binary_path,
+            b"#!/bin/sh\necho 'avalanche/1.10.3 [rpcchainvm=26]'\n",
+        )
+        .unwrap();
+        fs::set_permissions(&binary_path, fs::Permissions::from_mode(0o755)).unwrap();
+        let outcome = find_installed(
+            &binary_path,
+            Project::AvalancheGo,
+            "v1.10.3",
+            Platform::detect().unwrap(),
+        )
+        .unwrap()
+        .unwrap();
+        assert!(outcome.cache_hit);
+        assert!(outcome.archive_sha256.is_empty());
+        assert_eq!(outcome.version.unwrap().version, "1.10.3");
+        assert!(InstallReceipt::load(&binary_path).unwrap().is_none());
+    }
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-installer --lib -- install::test_copy_file_atomic --exact --show-output
+#[test]
+fn test_copy_file_atomic() {
+    use std::{os::unix::fs::PermissionsExt, process::Command};
+
+    let dir = Path::new(&random_manager::tmp_path(10, None).unwrap()).to_path_buf();
+    fs::create_dir_all(&dir).unwrap();
+
+    // replace a running binary
+    let dst = dir.join("sleep");
+    fs::copy("/bin/sleep", &dst).unwrap();
+    let mut child = Command::new(&dst).arg("30").spawn().unwrap();
+
+    let src = dir.join("new-binary");
+    fs::write(&src, b"#!/bin/sh\necho new\n").unwrap();
+    fs::set_permissions(&src, fs::Permissions::from_mode(0o755)).unwrap();
+    copy_file_atomic(&src, &dst).unwrap();
+    child.kill().unwrap();
+    child.wait().unwrap();
+
+    assert_eq!(fs::read(&dst).unwrap(), fs::read(&src).unwrap());
+    assert_eq!(
+        fs::metadata(&dst).unwrap().permissions().mode() & 0o777,
+        0o755
+    );
+    let mut names: Vec<String> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+        .collect();
+    names.sort();
+    assert_eq!(names, vec!["new-binary", "sleep"]);
+
+    // fails without leaving the temporary file behind
+    assert!(copy_file_atomic(dir.join("missing"), dir.join("other")).is_err());
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 2);
+
+    // symlinks are recreated, not followed
+    let secret = dir.join("secret");
+    fs::write(&secret, b"secret").unwrap();
+    let build_dir = dir.join("build");
+    fs::create_dir_all(build_dir.join("plugins")).unwrap();
+    fs::write(build_dir.join("avalanchego"), b"avalanchego").unwrap();
+    symlink(&secret, build_dir.join("plugins").join("evil")).unwrap();
+    symlink("/", build_dir.join("root")).unwrap();
+    let target_dir = dir.join("target");
+    copy_dir_all(&build_dir, &target_dir).unwrap();
+    assert_eq!(
+        fs::read(target_dir.join("avalanchego")).unwrap(),
+        b"avalanchego"
+    );
+    assert_eq!(
+        fs::read_link(target_dir.join("plugins").join("evil")).unwrap(),
+        secret
+    );
+    assert_eq!(
+        fs::read_link(target_dir.join("root")).unwrap(),
+        Path::new("/")
+    );
+    // copied again over the existing links
+    copy_dir_all(&build_dir, &target_dir).unwrap();
+    assert_eq!(fs::read_dir(target_dir.join("plugins")).unwrap().count(), 1);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
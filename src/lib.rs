@@ -1,3 +1,28 @@
+#[cfg(feature = "avalanchego")]
 pub mod avalanchego;
+pub mod batch;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod cancel;
+pub mod disk;
+pub mod download;
+pub mod extract;
 pub mod github;
+pub mod install;
+pub mod lock;
+pub mod network;
+pub mod platform;
+pub mod project;
+#[cfg(any(feature = "avalanchego", feature = "subnet_evm"))]
+mod release;
+pub mod store;
+#[cfg(feature = "subnet_evm")]
 pub mod subnet_evm;
+pub mod update;
+#[cfg(all(feature = "avalanchego", feature = "subnet_evm"))]
+pub mod updater;
+pub mod version;
+pub mod workspace;
+
+#[cfg(not(any(feature = "native-tls", feature = "rustls")))]
+compile_error!("enable the \"native-tls\" or \"rustls\" feature to reach GitHub over HTTPS");
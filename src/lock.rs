@@ -0,0 +1,361 @@
+use std::{
+    fs,
+    io::{self, Error, ErrorKind},
+    path::Path,
+    sync::Mutex,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    download::download_file, extract::ArchiveKind, github::Asset, install::InstallOutcome,
+    platform::Platform, project::Project, workspace::Workspace,
+};
+
+/// Default file name of the lockfile.
+pub const DEFAULT_LOCK_FILE_NAME: &str = "avalanche-installer.lock";
+
+/// Serializes the lockfile updates of concurrent installs in this process.
+static SYNC_MUTEX: Mutex<()> = Mutex::new(());
+
+/// Represents the release asset pinned for a project and platform.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct Pin {
+    pub project: Project,
+    pub platform: Platform,
+    /// Pinned release tag (e.g., "v1.10.3").
+    pub tag_name: String,
+    /// URL of the pinned release asset.
+    pub source_url: String,
+    /// Hex-encoded SHA-256 digest of the pinned release asset.
+    pub sha256: String,
+}
+
+/// Represents the lockfile (e.g., "avalanche-installer.lock") that pins
+/// the exact release assets to install, so that every install gets
+/// byte-identical binaries. Pins are recorded on the first install,
+/// honored on later installs, and only moved forward with "update".
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Default)]
+#[serde(rename_all = "snake_case")]
+pub struct Lockfile {
+    /// Sorted by project and platform, to keep the file diff-friendly.
+    pub pins: Vec<Pin>,
+}
+
+impl Lockfile {
+    /// Loads the lockfile, returning an empty one if the file does not exist.
+    pub fn load<P: AsRef<Path>>(file_path: P) -> io::Result<Self> {
+        let file_path = file_path.as_ref();
+        let d = match fs::read(file_path) {
+            Ok(d) => d,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e),
+        };
+        serde_json::from_slice(&d).map_err(|e| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("failed to decode lockfile {} ({})", file_path.display(), e),
+            )
+        })
+    }
+
+    /// Saves the lockfile atomically, so that readers never see it half-written.
+    pub fn sync<P: AsRef<Path>>(&self, file_path: P) -> io::Result<()> {
+        let file_path = file_path.as_ref();
+        log::info!("syncing lockfile to {}", file_path.display());
+        let mut d = serde_json::to_vec_pretty(self).map_err(|e| {
+            Error::new(
+                ErrorKind::Other,
+                format!("failed to serialize lockfile {}", e),
+            )
+        })?;
+        d.push(b'\n');
+        crate::install::write_file_atomic(file_path, &d)
+    }
+
+    /// Returns the pin for the project and platform, if any.
+    pub fn get(&self, project: Project, platform: Platform) -> Option<&Pin> {
+        self.pins
+            .iter()
+            .find(|p| p.project == project && p.platform == platform)
+    }
+
+    /// Adds the pin, replacing the existing one for the same project and platform.
+    pub fn insert(&mut self, pin: Pin) {
+        self.pins
+            .retain(|p| !(p.project == pin.project && p.platform == pin.platform));
+        self.pins.push(pin);
+        self.pins.sort_by(|a, b| {
+            (a.project, a.platform.to_string()).cmp(&(b.project, b.platform.to_string()))
+        });
+    }
+
+    /// Returns the pin to install for the project and platform, if any.
+    /// Returns an error if the requested "release_tag" differs from the pinned one,
+    /// in which case the lock must be updated first.
+    pub fn resolve(
+        &self,
+        project: Project,
+        platform: Platform,
+        release_tag: Option<&str>,
+    ) -> io::Result<Option<&Pin>> {
+        let pin = match self.get(project, platform) {
+            Some(v) => v,
+            None => return Ok(None),
+        };
+        match release_tag {
+            Some(tag_name) if tag_name != "latest" && tag_name != pin.tag_name => Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "{} for {} is locked to {}, update the lock to install {}",
+                    project, platform, pin.tag_name, tag_name
+                ),
+            )),
+            _ => Ok(Some(pin)),
+        }
+    }
+}
+
+/// Returns the pin for the project and platform from the lockfile, if set,
+/// after checking it against the requested "release_tag".
+pub fn find_pin(
+    lock_file: Option<&Path>,
+    project: Project,
+    platform: Platform,
+    release_tag: Option<&str>,
+) -> io::Result<Option<Pin>> {
+    let lock_file = match lock_file {
+        Some(v) => v,
+        None => return Ok(None),
+    };
+    let lockfile = Lockfile::load(lock_file)?;
+    let pin = lockfile.resolve(project, platform, release_tag)?.cloned();
+    if let Some(pin) = &pin {
+        log::info!(
+            "using {} {} for {} locked in {}",
+            project,
+            pin.tag_name,
+            platform,
+            lock_file.display()
+        );
+    }
+    Ok(pin)
+}
+
+/// Returns true if the installed binary came from the pinned asset, or if
+/// nothing is pinned. Binaries found without their install receipt have no
+/// recorded asset digest, so they never match a pin and get reinstalled.
+pub fn matches_pin(pinned: Option<&Pin>, installed: &InstallOutcome) -> bool {
+    let pin = match pinned {
+        Some(v) => v,
+        None => return true,
+    };
+    if installed.archive_sha256 != pin.sha256 {
+        log::warn!(
+            "{} was installed from the asset with sha256 '{}', locked {}",
+            installed.binary_path.display(),
+            installed.archive_sha256,
+            pin.sha256
+        );
+        return false;
+    }
+    true
+}
+
+/// Checks the downloaded asset against the pin from "find_pin", if any.
+/// Returns an error if the downloaded digest differs from the pinned one.
+pub fn check_pin(pinned: Option<&Pin>, downloaded: &Pin) -> io::Result<()> {
+    if let Some(pin) = pinned {
+        if pin.sha256 != downloaded.sha256 {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "{} has sha256 {}, locked {}",
+                    downloaded.source_url, downloaded.sha256, pin.sha256
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Records the installed asset in the lockfile if nothing is pinned yet
+/// for its project and platform. Call it only once the install succeeded,
+/// so that an asset that failed to unpack or run never gets pinned.
+pub fn record_pin(lock_file: Option<&Path>, installed: &InstallOutcome) -> io::Result<()> {
+    let lock_file = match lock_file {
+        Some(v) => v,
+        None => return Ok(()),
+    };
+    let _guard = SYNC_MUTEX.lock().unwrap_or_else(|e| e.into_inner());
+    let mut lockfile = Lockfile::load(lock_file)?;
+    if lockfile
+        .get(installed.project, installed.platform)
+        .is_some()
+    {
+        return Ok(());
+    }
+    lockfile.insert(Pin {
+        project: installed.project,
+        platform: installed.platform,
+        tag_name: installed.tag_name.clone(),
+        source_url: installed.source_url.clone(),
+        sha256: installed.archive_sha256.clone(),
+    });
+    lockfile.sync(lock_file)
+}
+
+/// Moves the pin for the project and platform forward to the "release_tag",
+/// or to the latest release if none, by resolving and hashing its asset.
+/// Returns the new pin.
+pub async fn update<P: AsRef<Path>>(
+    lock_file: P,
+    project: Project,
+    platform: Platform,
+    release_tag: Option<String>,
+) -> io::Result<Pin> {
+    let (tag_name, asset, archive_kind) = resolve_asset(project, platform, release_tag).await?;
+
+    let workspace = Workspace::new(None, false)?;
+    let tmp_file_path = workspace.join_str(format!("{}{}", project, archive_kind.suffix()))?;
+    let downloaded = download_file(&asset.browser_download_url, &tmp_file_path).await;
+    drop(workspace);
+    let pin = Pin {
+        project,
+        platform,
+        tag_name,
+        source_url: asset.browser_download_url,
+        sha256: downloaded?.sha256,
+    };
+
+    let lock_file = lock_file.as_ref();
+    let _guard = SYNC_MUTEX.lock().unwrap_or_else(|e| e.into_inner());
+    let mut lockfile = Lockfile::load(lock_file)?;
+    if let Some(prev) = lockfile.get(project, platform) {
+        log::info!(
+            "updating {} for {} from {} to {}",
+            project,
+            platform,
+            prev.tag_name,
+            pin.tag_name
+        );
+    }
+    lockfile.insert(pin.clone());
+    lockfile.sync(lock_file)?;
+    Ok(pin)
+}
+
+/// Resolves the release asset of the project, if its cargo feature is enabled.
+#[allow(unused_variables)]
+async fn resolve_asset(
+    project: Project,
+    platform: Platform,
+    release_tag: Option<String>,
+) -> io::Result<(String, Asset, ArchiveKind)> {
+    match project {
+        #[cfg(feature = "avalanchego")]
+        Project::AvalancheGo => {
+            crate::avalanchego::github::resolve_asset(&platform, release_tag).await
+        }
+        #[cfg(feature = "subnet_evm")]
+        Project::SubnetEvm => {
+            crate::subnet_evm::github::resolve_asset(&platform, release_tag).await
+        }
+        #[allow(unreachable_patterns)]
+        project => Err(project.not_enabled()),
+    }
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-installer --lib -- lock::test_lockfile --exact --show-output
+#[test]
+fn test_lockfile() {
+    let linux = Platform::parse("linux/amd64").unwrap();
+    let pin = |project: Project, platform: Platform, tag_name: &str| Pin {
+        project,
+        platform,
+        tag_name: tag_name.to_string(),
+        source_url: format!("https://example.com/{}/{}.tar.gz", project, tag_name),
+        sha256: format!("{}-sha256", tag_name),
+    };
+
+    let lock_file = random_manager::tmp_path(10, Some(".lock")).unwrap();
+    let lock_file = Path::new(&lock_file);
+    assert_eq!(Lockfile::load(lock_file).unwrap(), Lockfile::default());
+    assert!(find_pin(Some(lock_file), Project::AvalancheGo, linux, None)
+        .unwrap()
+        .is_none());
+
+    // first install records the pin
+    let installed = |pin: &Pin, archive_sha256: &str| InstallOutcome {
+        project: pin.project,
+        binary_path: Path::new("/tmp").join(pin.project.repo()),
+        tag_name: pin.tag_name.clone(),
+        platform: pin.platform,
+        source_url: pin.source_url.clone(),
+        archive_sha256: archive_sha256.to_string(),
+        bytes_downloaded: 0,
+        cache_hit: false,
+        elapsed: Default::default(),
+        version: None,
+    };
+    let v1 = pin(Project::AvalancheGo, linux, "v1.10.3");
+    check_pin(None, &v1).unwrap();
+    record_pin(Some(lock_file), &installed(&v1, &v1.sha256)).unwrap();
+    let subnet_evm = pin(Project::SubnetEvm, linux, "v0.5.1");
+    record_pin(Some(lock_file), &installed(&subnet_evm, &subnet_evm.sha256)).unwrap();
+    // never replaces the existing pin
+    let v2 = pin(Project::AvalancheGo, linux, "v1.10.4");
+    record_pin(Some(lock_file), &installed(&v2, &v2.sha256)).unwrap();
+    record_pin(None, &installed(&v2, &v2.sha256)).unwrap();
+    let lockfile = Lockfile::load(lock_file).unwrap();
+    assert_eq!(lockfile.pins.len(), 2);
+    assert_eq!(lockfile.get(Project::AvalancheGo, linux), Some(&v1));
+
+    // later installs honor the pin
+    let pinned = find_pin(Some(lock_file), Project::AvalancheGo, linux, None)
+        .unwrap()
+        .unwrap();
+    assert_eq!(pinned, v1);
+    assert!(find_pin(
+        Some(lock_file),
+        Project::AvalancheGo,
+        linux,
+        Some("v1.10.3")
+    )
+    .is_ok());
+    assert!(find_pin(
+        Some(lock_file),
+        Project::AvalancheGo,
+        linux,
+        Some("v1.10.4")
+    )
+    .is_err());
+    check_pin(Some(&pinned), &v1).unwrap();
+    let mut tampered = v1.clone();
+    tampered.sha256 = String::from("tampered");
+    let err = check_pin(Some(&pinned), &tampered).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+
+    // saved atomically, without leaving the temporary file behind
+    let lock_dir = lock_file.parent().unwrap();
+    let lock_file_name = lock_file.file_name().unwrap().to_string_lossy().to_string();
+    assert!(!fs::read_dir(lock_dir).unwrap().any(|e| {
+        let file_name = e.unwrap().file_name().to_string_lossy().to_string();
+        file_name.starts_with(&format!(".{}.", lock_file_name))
+    }));
+
+    // cache hits must come from the pinned asset
+    let installed = |archive_sha256: &str| {
+        let mut outcome = installed(&v1, archive_sha256);
+        outcome.cache_hit = true;
+        outcome
+    };
+    assert!(matches_pin(Some(&pinned), &installed("v1.10.3-sha256")));
+    assert!(!matches_pin(Some(&pinned), &installed("tampered")));
+    // found without the install receipt
+    assert!(!matches_pin(Some(&pinned), &installed("")));
+    assert!(matches_pin(None, &installed("")));
+
+    fs::remove_file(lock_file).unwrap();
+}
@@ -0,0 +1,149 @@
+use std::{
+    fmt,
+    io::{self, Error, ErrorKind},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Serialize};
+
+/// Represents the Avalanche network to install for.
+/// Serializes to the "avalanchego" "--network-id" value
+/// (e.g., "mainnet", "fuji", "local", "1337").
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Hash, Clone, Copy)]
+#[serde(try_from = "String", into = "String")]
+pub enum Network {
+    Mainnet,
+    Fuji,
+    Local,
+    /// Custom network with its own network ID.
+    Custom(u32),
+}
+
+/// ref. https://github.com/ava-labs/avalanchego/blob/master/utils/constants/network_ids.go
+pub const MAINNET_ID: u32 = 1;
+pub const FUJI_ID: u32 = 5;
+pub const LOCAL_ID: u32 = 12345;
+
+/// ref. https://doc.rust-lang.org/std/string/trait.ToString.html
+/// ref. https://doc.rust-lang.org/std/fmt/trait.Display.html
+/// Use "Self.to_string()" to directly invoke this
+impl fmt::Display for Network {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Network::Mainnet => write!(f, "mainnet"),
+            Network::Fuji => write!(f, "fuji"),
+            Network::Local => write!(f, "local"),
+            Network::Custom(id) => write!(f, "{}", id),
+        }
+    }
+}
+
+impl FromStr for Network {
+    type Err = Error;
+
+    fn from_str(s: &str) -> io::Result<Self> {
+        Self::new(s)
+    }
+}
+
+impl TryFrom<String> for Network {
+    type Error = Error;
+
+    fn try_from(s: String) -> io::Result<Self> {
+        Self::new(&s)
+    }
+}
+
+impl From<Network> for String {
+    fn from(n: Network) -> Self {
+        n.to_string()
+    }
+}
+
+impl Network {
+    /// Parses the network name or the numeric network ID.
+    pub fn new(network: &str) -> io::Result<Self> {
+        match network.to_lowercase().as_str() {
+            "mainnet" => Ok(Network::Mainnet),
+            "fuji" => Ok(Network::Fuji),
+            "local" => Ok(Network::Local),
+            v => match v.parse::<u32>() {
+                Ok(id) => Ok(Self::from_id(id)),
+                Err(_) => Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("unknown network {}", network),
+                )),
+            },
+        }
+    }
+
+    pub fn from_id(network_id: u32) -> Self {
+        match network_id {
+            MAINNET_ID => Network::Mainnet,
+            FUJI_ID => Network::Fuji,
+            LOCAL_ID => Network::Local,
+            id => Network::Custom(id),
+        }
+    }
+
+    pub fn network_id(&self) -> u32 {
+        match self {
+            Network::Mainnet => MAINNET_ID,
+            Network::Fuji => FUJI_ID,
+            Network::Local => LOCAL_ID,
+            Network::Custom(id) => *id,
+        }
+    }
+
+    /// Returns the name used for the per-network directories
+    /// (e.g., "mainnet", "network-1337").
+    pub fn dir_name(&self) -> String {
+        match self {
+            Network::Custom(id) => format!("network-{}", id),
+            _ => self.to_string(),
+        }
+    }
+
+    /// Returns the per-network directory under the root directory
+    /// (e.g., "/data/avalanche/fuji"), to keep installs and data
+    /// of different networks apart on the same host.
+    pub fn dir<P: AsRef<Path>>(&self, root_dir: P) -> PathBuf {
+        root_dir.as_ref().join(self.dir_name())
+    }
+
+    /// Returns the default "--bootstrap-ips" and "--bootstrap-ids" values.
+    /// Mainnet and fuji use the beacons built into "avalanchego",
+    /// while local and custom networks start without any beacons,
+    /// as the first node of the network.
+    pub fn bootstrap(&self) -> Option<(String, String)> {
+        match self {
+            Network::Mainnet | Network::Fuji => None,
+            Network::Local | Network::Custom(_) => Some((String::new(), String::new())),
+        }
+    }
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-installer --lib -- network::test_network --exact --show-output
+#[test]
+fn test_network() {
+    for (s, expected, id) in [
+        ("mainnet", Network::Mainnet, 1),
+        ("Fuji", Network::Fuji, 5),
+        ("5", Network::Fuji, 5),
+        ("local", Network::Local, 12345),
+        ("1337", Network::Custom(1337), 1337),
+    ] {
+        let network = Network::new(s).unwrap();
+        assert_eq!(network, expected);
+        assert_eq!(network.network_id(), id);
+        assert_eq!(Network::new(&network.to_string()).unwrap(), network);
+    }
+    assert!(Network::new("devnet").is_err());
+
+    assert_eq!(Network::Fuji.dir("/data"), PathBuf::from("/data/fuji"));
+    assert_eq!(
+        Network::Custom(1337).dir("/data"),
+        PathBuf::from("/data/network-1337")
+    );
+}
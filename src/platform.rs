@@ -0,0 +1,324 @@
+use std::{
+    env, fmt,
+    io::{self, Error, ErrorKind},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Serialize};
+
+/// Represents the release "arch".
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Hash, Clone, Copy)]
+pub enum Arch {
+    #[serde(rename = "amd64", alias = "x86_64")]
+    Amd64,
+    #[serde(rename = "arm64", alias = "aarch64")]
+    Arm64,
+}
+
+/// ref. https://doc.rust-lang.org/std/string/trait.ToString.html
+/// ref. https://doc.rust-lang.org/std/fmt/trait.Display.html
+/// Use "Self.to_string()" to directly invoke this
+impl fmt::Display for Arch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Arch::Amd64 => write!(f, "amd64"),
+            Arch::Arm64 => write!(f, "arm64"),
+        }
+    }
+}
+
+impl FromStr for Arch {
+    type Err = Error;
+
+    fn from_str(s: &str) -> io::Result<Self> {
+        Self::new(s)
+    }
+}
+
+impl Arch {
+    /// Parses the Go-style arch (e.g., "amd64") or the Rust-style arch (e.g., "x86_64").
+    pub fn new(arch: &str) -> io::Result<Self> {
+        match arch {
+            "amd64" | "x86_64" => Ok(Arch::Amd64),
+            "arm64" | "aarch64" => Ok(Arch::Arm64),
+            _ => Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("unknown arch {}", arch),
+            )),
+        }
+    }
+
+    /// Detects the arch of the local system.
+    pub fn detect() -> io::Result<Self> {
+        Self::new(env::consts::ARCH).map_err(|_| {
+            Error::new(
+                ErrorKind::Unsupported,
+                format!("unsupported host arch '{}'", env::consts::ARCH),
+            )
+        })
+    }
+
+    /// Returns the tokens that denote this arch in release asset names.
+    pub fn asset_tokens(&self) -> &'static [&'static str] {
+        match self {
+            Arch::Amd64 => &["amd64", "x86_64"],
+            Arch::Arm64 => &["arm64", "aarch64"],
+        }
+    }
+}
+
+/// All tokens that denote an arch in release asset names.
+pub const ARCH_ASSET_TOKENS: &[&str] = &["amd64", "x86_64", "arm64", "aarch64"];
+
+/// Represents the release "os".
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Hash, Clone, Copy)]
+pub enum Os {
+    #[serde(rename = "macos", alias = "darwin")]
+    MacOs,
+    #[serde(rename = "linux")]
+    Linux,
+    #[serde(rename = "win", alias = "windows")]
+    Windows,
+}
+
+/// ref. https://doc.rust-lang.org/std/string/trait.ToString.html
+/// ref. https://doc.rust-lang.org/std/fmt/trait.Display.html
+/// Use "Self.to_string()" to directly invoke this
+impl fmt::Display for Os {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Os::MacOs => write!(f, "macos"),
+            Os::Linux => write!(f, "linux"),
+            Os::Windows => write!(f, "win"),
+        }
+    }
+}
+
+impl FromStr for Os {
+    type Err = Error;
+
+    fn from_str(s: &str) -> io::Result<Self> {
+        Self::new(s)
+    }
+}
+
+impl Os {
+    /// Parses the release os (e.g., "macos"), the Go-style os (e.g., "darwin"),
+    /// or the Rust-style os (e.g., "windows").
+    pub fn new(os: &str) -> io::Result<Self> {
+        match os {
+            "macos" | "darwin" => Ok(Os::MacOs),
+            "linux" => Ok(Os::Linux),
+            "win" | "windows" => Ok(Os::Windows),
+            _ => Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("unknown os {}", os),
+            )),
+        }
+    }
+
+    /// Detects the os of the local system.
+    pub fn detect() -> io::Result<Self> {
+        Self::new(env::consts::OS).map_err(|_| {
+            Error::new(
+                ErrorKind::Unsupported,
+                format!("unsupported host os '{}'", env::consts::OS),
+            )
+        })
+    }
+
+    /// Returns the tokens that denote this os in release asset names.
+    pub fn asset_tokens(&self) -> &'static [&'static str] {
+        match self {
+            Os::MacOs => &["macos", "darwin"],
+            Os::Linux => &["linux"],
+            Os::Windows => &["win", "windows"],
+        }
+    }
+}
+
+/// Represents the release platform, a pair of "os" and "arch".
+/// Serializes to the "os/arch" string with the release os names
+/// (e.g., "linux/amd64", "macos/arm64", "win/amd64"), and deserializes
+/// from anything "parse" accepts (e.g., "darwin/arm64").
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Hash, Clone, Copy)]
+#[serde(try_from = "String", into = "String")]
+pub struct Platform {
+    pub os: Os,
+    pub arch: Arch,
+}
+
+/// ref. https://doc.rust-lang.org/std/string/trait.ToString.html
+/// ref. https://doc.rust-lang.org/std/fmt/trait.Display.html
+/// Use "Self.to_string()" to directly invoke this
+impl fmt::Display for Platform {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{}", self.os, self.arch)
+    }
+}
+
+impl FromStr for Platform {
+    type Err = Error;
+
+    fn from_str(s: &str) -> io::Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl TryFrom<String> for Platform {
+    type Error = Error;
+
+    fn try_from(s: String) -> io::Result<Self> {
+        Self::parse(&s)
+    }
+}
+
+impl From<Platform> for String {
+    fn from(p: Platform) -> Self {
+        p.to_string()
+    }
+}
+
+impl Platform {
+    pub const fn new(os: Os, arch: Arch) -> Self {
+        Self { os, arch }
+    }
+
+    /// Detects the platform of the local system.
+    pub fn detect() -> io::Result<Self> {
+        Ok(Self {
+            os: Os::detect()?,
+            arch: Arch::detect()?,
+        })
+    }
+
+    /// Returns the platform with the given "os" and "arch",
+    /// detecting the missing ones from the local system.
+    pub fn new_or_detect(os: Option<Os>, arch: Option<Arch>) -> io::Result<Self> {
+        let os = match os {
+            Some(v) => v,
+            None => Os::detect()?,
+        };
+        let arch = match arch {
+            Some(v) => v,
+            None => Arch::detect()?,
+        };
+        Ok(Self { os, arch })
+    }
+
+    /// Parses the Go-style "os/arch" (e.g., "linux/amd64", "darwin/arm64")
+    /// or the Rust target triple (e.g., "x86_64-unknown-linux-gnu", "aarch64-apple-darwin").
+    pub fn parse(s: &str) -> io::Result<Self> {
+        if let Some((os, arch)) = s.split_once('/') {
+            return Ok(Self {
+                os: Os::new(os)?,
+                arch: Arch::new(arch)?,
+            });
+        }
+
+        // e.g., "x86_64-unknown-linux-gnu", "aarch64-apple-darwin", "x86_64-pc-windows-msvc"
+        let mut parts = s.split('-');
+        let arch = match parts.next() {
+            Some(v) => Arch::new(v)?,
+            None => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("unknown platform {}", s),
+                ))
+            }
+        };
+        let os = parts.find_map(|p| Os::new(p).ok()).ok_or_else(|| {
+            Error::new(ErrorKind::InvalidInput, format!("unknown platform {}", s))
+        })?;
+        Ok(Self { os, arch })
+    }
+
+    /// Returns an error listing the supported platforms of the project,
+    /// if this platform is not one of them.
+    pub fn ensure_supported(&self, project: &str, supported: &[Platform]) -> io::Result<()> {
+        if supported.contains(self) {
+            return Ok(());
+        }
+        Err(Error::new(
+            ErrorKind::Unsupported,
+            format!(
+                "unsupported platform '{}' for {} (supported: {})",
+                self,
+                project,
+                supported
+                    .iter()
+                    .map(|p| p.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        ))
+    }
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-installer --lib -- platform::test_platform --exact --show-output
+#[test]
+fn test_platform() {
+    for (s, expected) in [
+        ("linux/amd64", Platform::new(Os::Linux, Arch::Amd64)),
+        ("linux/arm64", Platform::new(Os::Linux, Arch::Arm64)),
+        ("darwin/arm64", Platform::new(Os::MacOs, Arch::Arm64)),
+        ("macos/amd64", Platform::new(Os::MacOs, Arch::Amd64)),
+        ("windows/amd64", Platform::new(Os::Windows, Arch::Amd64)),
+        (
+            "x86_64-unknown-linux-gnu",
+            Platform::new(Os::Linux, Arch::Amd64),
+        ),
+        (
+            "aarch64-unknown-linux-musl",
+            Platform::new(Os::Linux, Arch::Arm64),
+        ),
+        (
+            "aarch64-apple-darwin",
+            Platform::new(Os::MacOs, Arch::Arm64),
+        ),
+        (
+            "x86_64-pc-windows-msvc",
+            Platform::new(Os::Windows, Arch::Amd64),
+        ),
+    ] {
+        assert_eq!(Platform::parse(s).unwrap(), expected, "{}", s);
+        assert_eq!(s.parse::<Platform>().unwrap(), expected, "{}", s);
+    }
+    for s in [
+        "",
+        "linux",
+        "linux/386",
+        "freebsd/amd64",
+        "riscv64gc-unknown-linux-gnu",
+    ] {
+        assert!(Platform::parse(s).is_err(), "{}", s);
+    }
+
+    // serde round-trip
+    let platform = Platform::new(Os::MacOs, Arch::Arm64);
+    let encoded = serde_json::to_string(&platform).unwrap();
+    assert_eq!(encoded, "\"macos/arm64\"");
+    assert_eq!(
+        serde_json::from_str::<Platform>(&encoded).unwrap(),
+        platform
+    );
+    assert_eq!(
+        serde_json::from_str::<Platform>("\"darwin/arm64\"").unwrap(),
+        platform
+    );
+    assert!(serde_json::from_str::<Platform>("\"darwin/386\"").is_err());
+
+    let supported = &[
+        Platform::new(Os::Linux, Arch::Amd64),
+        Platform::new(Os::MacOs, Arch::Arm64),
+    ];
+    platform.ensure_supported("subnet-evm", supported).unwrap();
+    let err = Platform::new(Os::Windows, Arch::Amd64)
+        .ensure_supported("subnet-evm", supported)
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unsupported);
+    assert_eq!(
+        err.to_string(),
+        "unsupported platform 'win/amd64' for subnet-evm (supported: linux/amd64, macos/arm64)"
+    );
+}
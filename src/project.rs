@@ -0,0 +1,96 @@
+use std::{
+    fmt,
+    io::{self, Error, ErrorKind},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Serialize};
+
+/// Represents the project whose release binaries are installed.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Hash, Clone, Copy, PartialOrd, Ord)]
+pub enum Project {
+    #[serde(rename = "avalanchego")]
+    AvalancheGo,
+    #[serde(rename = "subnet-evm")]
+    SubnetEvm,
+}
+
+/// ref. https://doc.rust-lang.org/std/string/trait.ToString.html
+/// ref. https://doc.rust-lang.org/std/fmt/trait.Display.html
+/// Use "Self.to_string()" to directly invoke this
+impl fmt::Display for Project {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.repo())
+    }
+}
+
+impl FromStr for Project {
+    type Err = Error;
+
+    fn from_str(s: &str) -> io::Result<Self> {
+        Self::new(s)
+    }
+}
+
+impl Project {
+    pub fn new(project: &str) -> io::Result<Self> {
+        match project.to_lowercase().as_str() {
+            "avalanchego" | "avalanche" => Ok(Project::AvalancheGo),
+            "subnet-evm" | "subnet_evm" => Ok(Project::SubnetEvm),
+            _ => Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("unknown project {}", project),
+            )),
+        }
+    }
+
+    /// Returns the GitHub organization of the project.
+    pub fn org(&self) -> &'static str {
+        "ava-labs"
+    }
+
+    /// Returns the GitHub repository name of the project.
+    pub fn repo(&self) -> &'static str {
+        match self {
+            Project::AvalancheGo => "avalanchego",
+            Project::SubnetEvm => "subnet-evm",
+        }
+    }
+
+    /// Returns the GitHub release page of the project.
+    pub fn releases_url(&self) -> String {
+        format!("https://github.com/{}/{}/releases", self.org(), self.repo())
+    }
+
+    /// Returns the release tag to fall back to if the latest release
+    /// is a prerelease or cannot be resolved.
+    /// ref. <https://github.com/ava-labs/avalanchego/releases>
+    /// ref. <https://github.com/ava-labs/subnet-evm/releases>
+    pub const fn default_tag_name(&self) -> &'static str {
+        match self {
+            Project::AvalancheGo => "v1.10.3",
+            Project::SubnetEvm => "v0.5.1",
+        }
+    }
+
+    /// Returns the error for the project whose cargo feature is not enabled
+    /// (e.g., "subnet_evm" for "subnet-evm").
+    pub(crate) fn not_enabled(&self) -> Error {
+        Error::new(
+            ErrorKind::Unsupported,
+            format!(
+                "{} support is not enabled, build with the \"{}\" feature",
+                self,
+                self.repo().replace('-', "_")
+            ),
+        )
+    }
+
+    /// Infers the project from a release asset download URL
+    /// (e.g., "https://github.com/ava-labs/subnet-evm/releases/download/...").
+    pub fn from_url(url: &str) -> Option<Self> {
+        [Project::AvalancheGo, Project::SubnetEvm]
+            .into_iter()
+            .find(|p| url.starts_with(&format!("{}/", p.releases_url())))
+    }
+}
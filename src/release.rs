@@ -0,0 +1,140 @@
+use std::{
+    future::Future,
+    io::{self, Error, ErrorKind},
+    path::PathBuf,
+    time::Instant,
+};
+
+use crate::{
+    github::{self, ReleaseResponse},
+    install::{find_installed, InstallOutcome, Options},
+    lock,
+    platform::Platform,
+    project::Project,
+    version::run_blocking,
+};
+
+/// Skips the "install" if the "binary_path" already is the project binary
+/// of the resolved release tag and platform.
+/// Re-installs if the installed binary is of a different release, corrupted,
+/// or not installed from the asset pinned in the lockfile.
+/// The "install" is called with the "opts" resolved to the release tag.
+/// Returns the install outcome with "cache_hit" set if nothing was downloaded.
+pub(crate) async fn ensure_installed<F, Fut>(
+    project: Project,
+    opts: &Options,
+    binary_path: PathBuf,
+    install: F,
+) -> io::Result<InstallOutcome>
+where
+    F: FnOnce(Options) -> Fut,
+    Fut: Future<Output = io::Result<InstallOutcome>>,
+{
+    let started = Instant::now();
+    let platform = Platform::new_or_detect(opts.os, opts.arch)?;
+
+    // no need to fetch the release info if the tag is pinned
+    let locked = lock::find_pin(
+        opts.lock_file.as_deref(),
+        project,
+        platform,
+        opts.release_tag.as_deref(),
+    )?;
+    let tag_name = match (&opts.release_tag, &locked) {
+        (_, Some(pin)) => pin.tag_name.clone(),
+        (Some(v), None) if v != "latest" => v.clone(),
+        _ => opts
+            .cancel
+            .run(fetch_release_info(project, opts.release_tag.clone()))
+            .await?
+            .tag_name
+            .unwrap(),
+    };
+
+    let (installed_path, installed_tag) = (binary_path.clone(), tag_name.clone());
+    let installed =
+        run_blocking(move || find_installed(&installed_path, project, &installed_tag, platform))
+            .await?
+            .filter(|outcome| lock::matches_pin(locked.as_ref(), outcome));
+    if let Some(mut outcome) = installed {
+        if opts.verify_version && outcome.version.is_none() {
+            outcome = outcome.verify_version_blocking().await?;
+        }
+        outcome.elapsed = started.elapsed();
+        return Ok(outcome);
+    }
+
+    log::info!(
+        "installing {} {} to {}",
+        project,
+        tag_name,
+        binary_path.display()
+    );
+    let opts = Options {
+        release_tag: Some(tag_name),
+        ..opts.clone()
+    };
+    let mut outcome = install(opts).await?;
+    outcome.elapsed = started.elapsed();
+    Ok(outcome)
+}
+
+/// Fetches the release info for the "release_tag", or the latest release if none.
+/// Falls back to the project default tag if the latest release is a prerelease
+/// or cannot be resolved.
+pub(crate) async fn fetch_release_info(
+    project: Project,
+    release_tag: Option<String>,
+) -> io::Result<ReleaseResponse> {
+    let default_tag_name = project.default_tag_name();
+    if let Some(v) = release_tag {
+        // the release pages don't have "latest" tag
+        let tag_name = if v.eq("latest") {
+            log::warn!("falling back 'latest' to {default_tag_name}");
+            default_tag_name.to_owned()
+        } else {
+            v
+        };
+        return fetch_tagged_release_info(project, &tag_name).await;
+    }
+
+    log::info!("fetching the latest git tags");
+    let release_info = match github::fetch_release_with_retries(project.org(), project.repo(), None)
+        .await
+    {
+        Ok(v) => v,
+        Err(e) => {
+            log::warn!("failed to fetch the latest release {e} -- defaults to {default_tag_name}");
+            return fetch_tagged_release_info(project, default_tag_name).await;
+        }
+    };
+
+    if release_info.tag_name.is_none() {
+        log::warn!("release_info.tag_name not found -- defaults to {default_tag_name}");
+        return fetch_tagged_release_info(project, default_tag_name).await;
+    }
+    if release_info.prerelease {
+        log::warn!(
+            "latest release '{}' is prerelease, falling back to default tag name '{}'",
+            release_info.tag_name.unwrap(),
+            default_tag_name
+        );
+        return fetch_tagged_release_info(project, default_tag_name).await;
+    }
+    Ok(release_info)
+}
+
+async fn fetch_tagged_release_info(
+    project: Project,
+    tag_name: &str,
+) -> io::Result<ReleaseResponse> {
+    let release_info =
+        github::fetch_release_with_retries(project.org(), project.repo(), Some(tag_name)).await?;
+    if release_info.tag_name.is_none() {
+        return Err(Error::new(
+            ErrorKind::NotFound,
+            format!("release '{}' not found", tag_name),
+        ));
+    }
+    Ok(release_info)
+}
@@ -0,0 +1,196 @@
+use std::{
+    fs,
+    io::{self, Error, ErrorKind},
+    os::unix::fs::symlink,
+    path::{Component, Path, PathBuf},
+};
+
+use crate::network::Network;
+
+/// Represents the directory of installed "avalanchego" versions,
+/// with the "current" symlink pointing to the active one.
+///
+///  root_dir
+///    ├── current -> versions/v1.10.4
+///    └── versions
+///        ├── v1.10.3
+///        │   ├── avalanchego
+///        │   └── plugins
+///        └── v1.10.4
+///            ├── avalanchego
+///            └── plugins
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Store {
+    pub root_dir: PathBuf,
+}
+
+impl Store {
+    pub fn new<P: AsRef<Path>>(root_dir: P) -> Self {
+        Self {
+            root_dir: root_dir.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Creates the store under the per-network directory
+    /// (e.g., "{root_dir}/fuji"), so each network keeps its own versions.
+    pub fn new_for_network<P: AsRef<Path>>(root_dir: P, network: Network) -> Self {
+        Self::new(network.dir(root_dir))
+    }
+
+    /// Returns the directory that all versions are installed under.
+    pub fn versions_dir(&self) -> PathBuf {
+        self.root_dir.join("versions")
+    }
+
+    /// Returns the install directory for the release tag.
+    /// Returns an error if the tag is not a plain directory name
+    /// (see "validate_tag"), so nothing outside "versions" is touched.
+    pub fn version_dir(&self, tag_name: &str) -> io::Result<PathBuf> {
+        validate_tag(tag_name)?;
+        Ok(self.versions_dir().join(tag_name))
+    }
+
+    /// Returns the "current" symlink path, which points to the active version.
+    /// Run the active binary via "{root_dir}/current/avalanchego".
+    pub fn current_dir(&self) -> PathBuf {
+        self.root_dir.join("current")
+    }
+
+    /// Returns the release tag of the active version, or none if not set.
+    pub fn current_tag(&self) -> io::Result<Option<String>> {
+        let target = match fs::read_link(self.current_dir()) {
+            Ok(v) => v,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        Ok(target.file_name().map(|v| v.to_string_lossy().to_string()))
+    }
+
+    /// Lists the release tags of the installed versions, oldest first.
+    pub fn list(&self) -> io::Result<Vec<String>> {
+        let mut tags = Vec::new();
+        let entries = match fs::read_dir(self.versions_dir()) {
+            Ok(v) => v,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(tags),
+            Err(e) => return Err(e),
+        };
+        for entry in entries {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                tags.push(entry.file_name().to_string_lossy().to_string());
+            }
+        }
+        tags.sort_by(|a, b| {
+            match (
+                crate::update::parse_tag(a).ok(),
+                crate::update::parse_tag(b).ok(),
+            ) {
+                (Some(va), Some(vb)) => va.cmp(&vb),
+                _ => a.cmp(b),
+            }
+        });
+        Ok(tags)
+    }
+
+    /// Switches the active version to the release tag, by atomically
+    /// replacing the "current" symlink.
+    pub fn activate(&self, tag_name: &str) -> io::Result<()> {
+        let version_dir = self.version_dir(tag_name)?;
+        if !version_dir.join("avalanchego").exists() {
+            return Err(Error::new(
+                ErrorKind::NotFound,
+                format!("{} is not installed in {}", tag_name, version_dir.display()),
+            ));
+        }
+
+        // relative, so the root directory can be moved
+        let target = Path::new("versions").join(tag_name);
+        let tmp_link = self.root_dir.join(format!(".current-{}", tag_name));
+        let _ = fs::remove_file(&tmp_link);
+        symlink(&target, &tmp_link)?;
+        fs::rename(&tmp_link, self.current_dir())?;
+        log::info!(
+            "activated {} ({} -> {})",
+            tag_name,
+            self.current_dir().display(),
+            target.display()
+        );
+        Ok(())
+    }
+
+    /// Removes the installed version. Fails if it is the active version.
+    pub fn remove(&self, tag_name: &str) -> io::Result<()> {
+        validate_tag(tag_name)?;
+        if self.current_tag()?.as_deref() == Some(tag_name) {
+            return Err(Error::new(
+                ErrorKind::Other,
+                format!("cannot remove the active version {}", tag_name),
+            ));
+        }
+        let version_dir = self.version_dir(tag_name)?;
+        log::info!("removing {}", version_dir.display());
+        fs::remove_dir_all(version_dir)
+    }
+}
+
+/// Returns an error if the release tag cannot be used as the version
+/// directory name, such as an empty tag, a path (e.g., "v1/../.."),
+/// or a hidden name (e.g., "..").
+pub fn validate_tag(tag_name: &str) -> io::Result<()> {
+    let mut components = Path::new(tag_name).components();
+    let valid = match (components.next(), components.next()) {
+        (Some(Component::Normal(v)), None) => v == tag_name && !tag_name.starts_with('.'),
+        _ => false,
+    };
+    if !valid {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("invalid release tag '{}'", tag_name),
+        ));
+    }
+    Ok(())
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-installer --lib -- store::test_store --exact --show-output
+#[test]
+fn test_store() {
+    let root_dir = Path::new(&random_manager::tmp_path(10, None).unwrap()).to_path_buf();
+    let store = Store::new(&root_dir);
+
+    for tag_name in ["v1.10.3", "v1.11.0-fuji", "1.10.3"] {
+        validate_tag(tag_name).unwrap();
+    }
+    for tag_name in [
+        "",
+        ".",
+        "..",
+        "v1/../..",
+        "../v1.10.3",
+        "/etc",
+        "v1.10.3/",
+        ".current-v1",
+    ] {
+        let err = validate_tag(tag_name).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput, "{}", tag_name);
+        assert!(store.version_dir(tag_name).is_err(), "{}", tag_name);
+        assert!(store.activate(tag_name).is_err(), "{}", tag_name);
+        assert!(store.remove(tag_name).is_err(), "{}", tag_name);
+    }
+
+    for tag_name in ["v1.10.3", "v1.10.10"] {
+        let version_dir = store.version_dir(tag_name).unwrap();
+        fs::create_dir_all(&version_dir).unwrap();
+        fs::write(version_dir.join("avalanchego"), tag_name).unwrap();
+    }
+    assert_eq!(store.list().unwrap(), vec!["v1.10.3", "v1.10.10"]);
+    assert_eq!(store.current_tag().unwrap(), None);
+    store.activate("v1.10.10").unwrap();
+    assert_eq!(store.current_tag().unwrap().as_deref(), Some("v1.10.10"));
+    assert!(store.remove("v1.10.10").is_err());
+    store.remove("v1.10.3").unwrap();
+    assert_eq!(store.list().unwrap(), vec!["v1.10.10"]);
+    assert!(store.activate("v1.10.3").is_err());
+    assert!(root_dir.exists());
+
+    fs::remove_dir_all(&root_dir).unwrap();
+}
@@ -1,164 +1,143 @@
 use std::{
-    env, fmt,
     fs::{self, File},
-    io::{self, copy, Cursor, Error, ErrorKind},
+    io,
     os::unix::fs::PermissionsExt,
-    path::Path,
+    path::{Path, PathBuf},
+    time::Instant,
 };
 
-use compress_manager::DirDecoder;
-use tokio::time::{sleep, Duration};
+use crate::{
+    extract::{self, ArchiveKind},
+    install::{download_archive, InstallReceipt},
+    lock::{self, Pin},
+    project::Project,
+    workspace::Workspace,
+};
+
+pub use crate::{
+    download::download_file,
+    install::{InstallOutcome, Options},
+    platform::{Arch, Os, Platform},
+};
 
 /// Downloads the latest "subnet-evm" from the github release page.
 pub async fn download_latest(
     arch: Option<Arch>,
     os: Option<Os>,
     target_file_path: &str,
-) -> io::Result<()> {
+) -> io::Result<InstallOutcome> {
     download(arch, os, None, target_file_path).await
 }
 
 /// ref. <https://github.com/ava-labs/subnet-evm/releases>
-pub const DEFAULT_TAG_NAME: &str = "v0.5.1";
+pub const DEFAULT_TAG_NAME: &str = Project::SubnetEvm.default_tag_name();
 
+/// Platforms with the official "subnet-evm" release builds.
+/// ref. <https://github.com/ava-labs/subnet-evm/releases>
+pub const SUPPORTED_PLATFORMS: &[Platform] = &[
+    Platform::new(Os::Linux, Arch::Amd64),
+    Platform::new(Os::Linux, Arch::Arm64),
+    Platform::new(Os::MacOs, Arch::Amd64),
+    Platform::new(Os::MacOs, Arch::Arm64),
+];
+
+/// Downloads the official "subnet-evm" binary from the GitHub release page,
+/// and copies it to the "target_file_path".
+/// Returns the install outcome with the path to the binary.
+///
+/// Leave "release_tag" none to download the latest.
+///
+/// Leave "arch" and "os" empty to auto-detect from its local system.
 /// ref. <https://github.com/ava-labs/subnet-evm/releases>
 pub async fn download(
     arch: Option<Arch>,
     os: Option<Os>,
     release_tag: Option<String>,
     target_file_path: &str,
-) -> io::Result<()> {
-    // e.g., "v0.5.0"
-    let tag_name = if let Some(v) = release_tag {
-        // "https://github.com/ava-labs/subnet-evm/releases" doesn't have "latest" tag
-        if v.eq("latest") {
-            log::warn!("falling back 'latest' to {DEFAULT_TAG_NAME}");
-            DEFAULT_TAG_NAME.to_owned()
-        } else {
-            v
-        }
-    } else {
-        log::info!("fetching the latest git tags");
-        let mut release_info = crate::github::ReleaseResponse::default();
-        for round in 0..10 {
-            let info = match crate::github::fetch_latest_release("ava-labs", "subnet-evm").await {
-                Ok(v) => v,
-                Err(e) => {
-                    log::warn!(
-                        "failed fetch_latest_release {} -- retrying {}...",
-                        e,
-                        round + 1
-                    );
-                    sleep(Duration::from_secs((round + 1) * 3)).await;
-                    continue;
-                }
-            };
-
-            release_info = info;
-            if release_info.tag_name.is_some() {
-                break;
-            }
-
-            log::warn!("release_info.tag_name is None -- retrying {}...", round + 1);
-            sleep(Duration::from_secs((round + 1) * 3)).await;
-        }
-
-        if release_info.tag_name.is_none() {
-            log::warn!("release_info.tag_name not found -- defaults to {DEFAULT_TAG_NAME}");
-            release_info.tag_name = Some(DEFAULT_TAG_NAME.to_string());
-        }
-
-        if release_info.prerelease {
-            log::warn!(
-                "latest release '{}' is prerelease, falling back to default tag name '{}'",
-                release_info.tag_name.unwrap(),
-                DEFAULT_TAG_NAME
-            );
-            DEFAULT_TAG_NAME.to_string()
-        } else {
-            release_info.tag_name.unwrap()
-        }
-    };
+) -> io::Result<InstallOutcome> {
+    download_with_options(
+        &Options {
+            arch,
+            os,
+            release_tag,
+            ..Default::default()
+        },
+        target_file_path,
+    )
+    .await
+}
 
-    // ref. <https://github.com/ava-labs/subnet-evm/releases>
-    log::info!(
-        "detecting arch and platform for the release version tag {}",
-        tag_name
-    );
-    let arch = {
-        if arch.is_none() {
-            match env::consts::ARCH {
-                "x86_64" => String::from("amd64"),
-                "aarch64" => String::from("arm64"),
-                _ => String::from(""),
-            }
-        } else {
-            let arch = arch.unwrap();
-            arch.to_string()
-        }
-    };
+/// Same as "download" but with the options.
+pub async fn download_with_options(
+    opts: &Options,
+    target_file_path: &str,
+) -> io::Result<InstallOutcome> {
+    let started = Instant::now();
+    let platform = Platform::new_or_detect(opts.os, opts.arch)?;
+    platform.ensure_supported("subnet-evm", SUPPORTED_PLATFORMS)?;
+    if let Some(parent_dir) = Path::new(target_file_path).parent() {
+        crate::workspace::ensure_executable(parent_dir)?;
+    }
 
-    // ref. <https://github.com/ava-labs/subnet-evm/releases>
-    let (file_name, dir_decoder) = {
-        if os.is_none() {
-            if cfg!(target_os = "macos") {
-                (
-                    format!(
-                        "subnet-evm_{}_darwin_{arch}.tar.gz",
-                        tag_name.trim_start_matches("v")
-                    ),
-                    DirDecoder::TarGzip,
-                )
-            } else if cfg!(unix) {
-                (
-                    format!(
-                        "subnet-evm_{}_linux_{arch}.tar.gz",
-                        tag_name.trim_start_matches("v")
-                    ),
-                    DirDecoder::TarGzip,
-                )
-            } else {
-                return Err(Error::new(ErrorKind::Other, "unknown OS"));
-            }
-        } else {
-            let os = os.unwrap();
-            match os {
-                Os::MacOs => (
-                    format!(
-                        "subnet-evm_{}_darwin_{arch}.tar.gz",
-                        tag_name.trim_start_matches("v")
-                    ),
-                    DirDecoder::TarGzip,
-                ),
-                Os::Linux => (
-                    format!(
-                        "subnet-evm_{}_linux_{arch}.tar.gz",
-                        tag_name.trim_start_matches("v")
-                    ),
-                    DirDecoder::TarGzip,
-                ),
-                Os::Windows => return Err(Error::new(ErrorKind::Other, "windows not supported")),
-            }
+    let pinned = lock::find_pin(
+        opts.lock_file.as_deref(),
+        Project::SubnetEvm,
+        platform,
+        opts.release_tag.as_deref(),
+    )?;
+    let (tag_name, source_url, asset_size, archive_kind) = match &pinned {
+        Some(pin) => (
+            pin.tag_name.clone(),
+            pin.source_url.clone(),
+            None,
+            ArchiveKind::from_file_name(&pin.source_url)?,
+        ),
+        None => {
+            let (tag_name, asset, archive_kind) = opts
+                .cancel
+                .run(resolve_asset(&platform, opts.release_tag.clone()))
+                .await?;
+            (
+                tag_name,
+                asset.browser_download_url,
+                asset.size,
+                archive_kind,
+            )
         }
     };
-    if file_name.is_empty() {
-        return Err(Error::new(
-            ErrorKind::Other,
-            format!("unknown platform '{}'", env::consts::OS),
-        ));
-    }
-
-    log::info!("downloading latest subnet-evm '{}'", file_name);
-    let download_url = format!(
-        "https://github.com/ava-labs/subnet-evm/releases/download/{}/{}",
-        tag_name, file_name
-    );
-    let tmp_file_path = random_manager::tmp_path(10, Some(dir_decoder.suffix()))?;
-    download_file(&download_url, &tmp_file_path).await?;
 
-    let dst_dir_path = random_manager::tmp_path(10, None)?;
+    log::info!("downloading subnet-evm {} from '{}'", tag_name, source_url);
+    let workspace = Workspace::new(opts.workspace_root.as_deref(), opts.keep_workspace)?;
+    let tmp_file_path = workspace.join_str(format!("subnet-evm{}", archive_kind.suffix()))?;
+    let downloaded = download_archive(
+        opts,
+        &source_url,
+        &tmp_file_path,
+        asset_size,
+        workspace.path(),
+        Path::new(target_file_path).parent(),
+    )
+    .await?;
+    lock::check_pin(
+        pinned.as_ref(),
+        &Pin {
+            project: Project::SubnetEvm,
+            platform,
+            tag_name: tag_name.clone(),
+            source_url: source_url.clone(),
+            sha256: downloaded.sha256.clone(),
+        },
+    )?;
+
+    let dst_dir_path = workspace.join_str("unpacked")?;
     log::info!("unpacking {} to {}", tmp_file_path, dst_dir_path);
-    compress_manager::unpack_directory(&tmp_file_path, &dst_dir_path, dir_decoder.clone())?;
+    extract::unpack_with_cancel(
+        &tmp_file_path,
+        &dst_dir_path,
+        archive_kind,
+        &opts.unpack_limits,
+        &opts.cancel,
+    )?;
 
     // TODO: this can fail due to files being still busy...
     log::info!("cleaning up downloaded file {}", tmp_file_path);
@@ -176,98 +155,68 @@ pub async fn download(
         let f = File::open(&subnet_evm_path)?;
         f.set_permissions(PermissionsExt::from_mode(0o777))?;
     }
-    log::info!(
-        "copying {} to {target_file_path}",
-        subnet_evm_path.display()
-    );
-    fs::copy(&subnet_evm_path, &target_file_path)?;
-    fs::remove_file(&subnet_evm_path)?;
-
-    Ok(())
-}
-
-/// Represents the subnet-evm release "arch".
-#[derive(Eq, PartialEq, Clone)]
-pub enum Arch {
-    Amd64,
-    Arm64,
-}
-
-/// ref. https://doc.rust-lang.org/std/string/trait.ToString.html
-/// ref. https://doc.rust-lang.org/std/fmt/trait.Display.html
-/// Use "Self.to_string()" to directly invoke this
-impl fmt::Display for Arch {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            Arch::Amd64 => write!(f, "amd64"),
-            Arch::Arm64 => write!(f, "arm64"),
-        }
-    }
-}
-
-impl Arch {
-    pub fn new(arch: &str) -> io::Result<Self> {
-        match arch {
-            "amd64" => Ok(Arch::Amd64),
-            "arm64" => Ok(Arch::Arm64),
-            _ => Err(Error::new(
-                ErrorKind::InvalidInput,
-                format!("unknown arch {}", arch),
-            )),
-        }
-    }
-}
-
-/// Represents the subnet-evm release "os".
-#[derive(Eq, PartialEq, Clone)]
-pub enum Os {
-    MacOs,
-    Linux,
-    Windows,
-}
-
-/// ref. https://doc.rust-lang.org/std/string/trait.ToString.html
-/// ref. https://doc.rust-lang.org/std/fmt/trait.Display.html
-/// Use "Self.to_string()" to directly invoke this
-impl fmt::Display for Os {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            Os::MacOs => write!(f, "macos"),
-            Os::Linux => write!(f, "linux"),
-            Os::Windows => write!(f, "win"),
-        }
+    opts.cancel.check()?;
+    crate::install::copy_file_atomic(&subnet_evm_path, target_file_path)?;
+    drop(workspace);
+
+    let mut outcome = InstallOutcome {
+        project: Project::SubnetEvm,
+        binary_path: PathBuf::from(target_file_path),
+        tag_name,
+        platform,
+        source_url,
+        archive_sha256: downloaded.sha256,
+        bytes_downloaded: downloaded.bytes,
+        cache_hit: false,
+        elapsed: started.elapsed(),
+        version: None,
+    };
+    if opts.verify_version {
+        outcome = outcome.verify_version_blocking().await?;
     }
+    InstallReceipt::record(&outcome)?;
+    lock::record_pin(opts.lock_file.as_deref(), &outcome)?;
+    outcome.elapsed = started.elapsed();
+    Ok(outcome)
 }
 
-impl Os {
-    pub fn new(os: &str) -> io::Result<Self> {
-        match os {
-            "macos" => Ok(Os::MacOs),
-            "linux" => Ok(Os::Linux),
-            "win" => Ok(Os::Windows),
-            _ => Err(Error::new(
-                ErrorKind::InvalidInput,
-                format!("unknown os {}", os),
-            )),
-        }
-    }
+/// Same as "download_with_options" but skips the download if the "target_file_path"
+/// already is the "subnet-evm" binary of the resolved release tag and platform.
+/// Re-installs if the installed binary is of a different release, corrupted,
+/// or not installed from the asset pinned in the lockfile.
+/// Returns the install outcome with "cache_hit" set if nothing was downloaded.
+pub async fn ensure_installed(
+    opts: &Options,
+    target_file_path: &str,
+) -> io::Result<InstallOutcome> {
+    crate::release::ensure_installed(
+        Project::SubnetEvm,
+        opts,
+        PathBuf::from(target_file_path),
+        |opts| async move { download_with_options(&opts, target_file_path).await },
+    )
+    .await
 }
 
-/// Downloads a file to the "file_path".
-pub async fn download_file(ep: &str, file_path: &str) -> io::Result<()> {
-    log::info!("downloading the file via {}", ep);
-    let resp = reqwest::get(ep)
-        .await
-        .map_err(|e| Error::new(ErrorKind::Other, format!("failed reqwest::get {}", e)))?;
-
-    let mut content = Cursor::new(
-        resp.bytes()
-            .await
-            .map_err(|e| Error::new(ErrorKind::Other, format!("failed bytes {}", e)))?,
-    );
-
-    let mut f = File::create(file_path)?;
-    copy(&mut content, &mut f)?;
+/// Resolves the "release_tag", or the latest release if none,
+/// and selects its "subnet-evm" release asset for the platform.
+/// Returns the resolved tag, the asset, and the decoder for its archive type.
+pub async fn resolve_asset(
+    platform: &Platform,
+    release_tag: Option<String>,
+) -> io::Result<(String, crate::github::Asset, ArchiveKind)> {
+    platform.ensure_supported("subnet-evm", SUPPORTED_PLATFORMS)?;
+    let release_info = crate::release::fetch_release_info(Project::SubnetEvm, release_tag).await?;
+    // e.g., "v0.5.0"
+    let tag_name = release_info.tag_name.clone().unwrap();
 
-    Ok(())
+    // ref. <https://github.com/ava-labs/subnet-evm/releases>
+    let (asset, archive_kind) = release_info.select_asset(&crate::github::AssetQuery {
+        prefix: "subnet-evm",
+        os_tokens: platform.os.asset_tokens(),
+        arch_tokens: platform.arch.asset_tokens(),
+        known_arch_tokens: crate::platform::ARCH_ASSET_TOKENS,
+        allow_arch_agnostic: false,
+    })?;
+    Ok((tag_name, asset, archive_kind))
 }
@@ -0,0 +1,244 @@
+use std::{
+    io::{self, Error, ErrorKind},
+    path::Path,
+};
+
+use semver::{Version, VersionReq};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    github::ReleaseResponse, install::InstallReceipt, project::Project, version::VersionReport,
+};
+
+/// Represents how the installed version compares to the latest release.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateStatus {
+    UpToDate,
+    PatchAvailable,
+    MinorAvailable,
+    MajorAvailable,
+    /// The installed version is newer than the latest stable release
+    /// (e.g., a prerelease or a locally built binary).
+    InstalledNewer,
+}
+
+impl UpdateStatus {
+    /// Compares the installed version to the latest version.
+    pub fn compare(installed: &Version, latest: &Version) -> Self {
+        if installed > latest {
+            UpdateStatus::InstalledNewer
+        } else if installed == latest {
+            UpdateStatus::UpToDate
+        } else if installed.major != latest.major {
+            UpdateStatus::MajorAvailable
+        } else if installed.minor != latest.minor {
+            UpdateStatus::MinorAvailable
+        } else {
+            UpdateStatus::PatchAvailable
+        }
+    }
+
+    /// Returns true if a newer release is available.
+    pub fn is_update_available(&self) -> bool {
+        matches!(
+            self,
+            UpdateStatus::PatchAvailable
+                | UpdateStatus::MinorAvailable
+                | UpdateStatus::MajorAvailable
+        )
+    }
+}
+
+/// Represents the result of checking for an update.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct UpdateCheck {
+    pub project: Project,
+    pub installed: Version,
+    pub latest: Version,
+    /// Latest release tag (e.g., "v1.10.3").
+    pub latest_tag_name: String,
+    pub status: UpdateStatus,
+    /// Latest release page URL.
+    pub release_url: String,
+    /// Latest release notes in markdown.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub release_notes: Option<String>,
+}
+
+/// Parses the release tag (e.g., "v1.10.3") as a semantic version.
+pub fn parse_tag(tag_name: &str) -> io::Result<Version> {
+    Version::parse(tag_name.trim_start_matches('v')).map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("failed to parse release tag '{}' ({})", tag_name, e),
+        )
+    })
+}
+
+/// Returns the newest stable release tag that matches the version requirement
+/// (e.g., "^1.10"), or none if no release matches.
+/// Prereleases and tags that are not semantic versions are skipped.
+pub fn select_tag(releases: &[ReleaseResponse], req: &VersionReq) -> Option<String> {
+    releases
+        .iter()
+        .filter(|r| !r.prerelease)
+        .filter_map(|r| {
+            let tag_name = r.tag_name.as_ref()?;
+            let version = parse_tag(tag_name).ok()?;
+            if req.matches(&version) {
+                Some((version, tag_name.clone()))
+            } else {
+                None
+            }
+        })
+        .max_by(|a, b| a.0.cmp(&b.0))
+        .map(|(_, tag_name)| tag_name)
+}
+
+/// Resolves the version requirement to the newest matching stable release tag
+/// of the project, or to the latest stable release if no requirement is given.
+pub async fn resolve_tag(project: Project, req: Option<&VersionReq>) -> io::Result<String> {
+    let releases = crate::github::fetch_releases(project.org(), project.repo()).await?;
+    let req = req.cloned().unwrap_or(VersionReq::STAR);
+    select_tag(&releases, &req).ok_or_else(|| {
+        Error::new(
+            ErrorKind::NotFound,
+            format!("no stable {} release matches '{}'", project, req),
+        )
+    })
+}
+
+/// Determines the project and the version of the installed binary,
+/// from its "--version" output, or from its install receipt
+/// if the binary cannot run on the local system.
+pub fn installed_version<P: AsRef<Path>>(installed_path: P) -> io::Result<(Project, Version)> {
+    let installed_path = installed_path.as_ref();
+    let report_err = match VersionReport::from_binary(installed_path) {
+        Ok(report) => {
+            let project = Project::new(&report.name)?;
+            return Ok((project, parse_tag(&report.version)?));
+        }
+        Err(e) => e,
+    };
+
+    log::warn!(
+        "failed to get {} version ({}), falling back to install receipt",
+        installed_path.display(),
+        report_err
+    );
+    let receipt = InstallReceipt::load(installed_path)?.ok_or_else(|| {
+        Error::new(
+            ErrorKind::NotFound,
+            format!(
+                "cannot determine the version of {} ({}), and no install receipt found",
+                installed_path.display(),
+                report_err
+            ),
+        )
+    })?;
+    Ok((receipt.project, parse_tag(&receipt.tag_name)?))
+}
+
+/// Checks whether a newer stable release is available for the installed
+/// "avalanchego" or "subnet-evm" binary.
+pub async fn check_for_update<P: AsRef<Path>>(installed_path: P) -> io::Result<UpdateCheck> {
+    let installed_path = installed_path.as_ref().to_path_buf();
+    let (project, installed) =
+        crate::version::run_blocking(move || installed_version(installed_path)).await?;
+
+    let release_info =
+        crate::github::fetch_release_with_retries(project.org(), project.repo(), None).await?;
+    let latest_tag_name = release_info.tag_name.clone().ok_or_else(|| {
+        Error::new(
+            ErrorKind::NotFound,
+            format!("latest {} release not found", project),
+        )
+    })?;
+    if release_info.prerelease {
+        return Err(Error::new(
+            ErrorKind::Other,
+            format!(
+                "latest {} release '{}' is prerelease",
+                project, latest_tag_name
+            ),
+        ));
+    }
+    let latest = parse_tag(&latest_tag_name)?;
+
+    let status = UpdateStatus::compare(&installed, &latest);
+    log::info!(
+        "{} installed {}, latest {} ({:?})",
+        project,
+        installed,
+        latest,
+        status
+    );
+    Ok(UpdateCheck {
+        project,
+        installed,
+        latest,
+        release_url: release_info
+            .html_url
+            .unwrap_or_else(|| format!("{}/tag/{}", project.releases_url(), latest_tag_name)),
+        latest_tag_name,
+        status,
+        release_notes: release_info.body,
+    })
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-installer --lib -- update::test_select_tag --exact --show-output
+#[test]
+fn test_select_tag() {
+    let release = |tag_name: &str, prerelease: bool| ReleaseResponse {
+        tag_name: Some(tag_name.to_string()),
+        prerelease,
+        ..Default::default()
+    };
+    let releases = vec![
+        release("v1.11.0-fuji", true),
+        release("v1.10.4", false),
+        release("v1.10.3", false),
+        release("v1.9.16", false),
+        release("nightly", false),
+    ];
+    let select = |req: &str| select_tag(&releases, &VersionReq::parse(req).unwrap());
+    assert_eq!(select("^1.10"), Some("v1.10.4".to_string()));
+    assert_eq!(select("*"), Some("v1.10.4".to_string()));
+    assert_eq!(select("=1.10.3"), Some("v1.10.3".to_string()));
+    assert_eq!(select("~1.9"), Some("v1.9.16".to_string()));
+    assert_eq!(select(">=1.11"), None);
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-installer --lib -- update::test_update_status --exact --show-output
+#[test]
+fn test_update_status() {
+    let compare = |installed: &str, latest: &str| {
+        UpdateStatus::compare(&parse_tag(installed).unwrap(), &parse_tag(latest).unwrap())
+    };
+    assert_eq!(compare("v1.10.3", "v1.10.3"), UpdateStatus::UpToDate);
+    assert_eq!(compare("1.10.3", "v1.10.3"), UpdateStatus::UpToDate);
+    assert_eq!(compare("v1.10.3", "v1.10.4"), UpdateStatus::PatchAvailable);
+    assert_eq!(compare("v1.10.3", "v1.11.0"), UpdateStatus::MinorAvailable);
+    assert_eq!(compare("v1.10.3", "v2.0.0"), UpdateStatus::MajorAvailable);
+    assert_eq!(compare("v1.10.4", "v1.10.3"), UpdateStatus::InstalledNewer);
+    assert!(compare("v1.10.3", "v1.10.4").is_update_available());
+    assert!(!compare("v1.10.3", "v1.10.3").is_update_available());
+    assert!(!compare("v1.10.4", "v1.10.3").is_update_available());
+
+    // a prerelease sorts before its release, and after the previous one
+    assert_eq!(
+        compare("v1.11.0-fuji", "v1.11.0"),
+        UpdateStatus::PatchAvailable
+    );
+    assert_eq!(
+        compare("v1.11.0-fuji", "v1.10.4"),
+        UpdateStatus::InstalledNewer
+    );
+
+    for tag_name in ["", "nightly", "v1.10", "v1.10.x"] {
+        let err = parse_tag(tag_name).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput, "{}", tag_name);
+    }
+}
@@ -0,0 +1,539 @@
+use std::{
+    fmt, fs,
+    io::{self, Error, ErrorKind},
+    path::Path,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use semver::{Comparator, Op, Version, VersionReq};
+use serde::{Deserialize, Serialize};
+use tokio::{process::Command, time::sleep};
+
+use crate::{
+    avalanchego, install,
+    platform::Platform,
+    project::Project,
+    store::Store,
+    subnet_evm,
+    update::{parse_tag, select_tag, UpdateStatus},
+    version::{run_blocking, VersionReport},
+};
+
+/// Represents the largest version bump that the updater applies automatically.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdatePolicy {
+    /// Only applies patch updates (e.g., "v1.10.3" to "v1.10.4").
+    Patch,
+    /// Applies patch and minor updates (e.g., "v1.10.3" to "v1.11.0").
+    #[default]
+    Minor,
+    /// Applies all updates.
+    Major,
+}
+
+impl UpdatePolicy {
+    /// Returns true if the policy allows the update.
+    pub fn allows(&self, status: UpdateStatus) -> bool {
+        match status {
+            UpdateStatus::PatchAvailable => true,
+            UpdateStatus::MinorAvailable => *self != UpdatePolicy::Patch,
+            UpdateStatus::MajorAvailable => *self == UpdatePolicy::Major,
+            UpdateStatus::UpToDate | UpdateStatus::InstalledNewer => false,
+        }
+    }
+
+    /// Returns the requirement for the releases that the policy allows
+    /// updating the "installed" version to: "~installed" for patch updates,
+    /// "^installed" for minor updates, and ">=installed" for all updates.
+    /// Follows the semver rules, so minor updates of "v0.x" releases
+    /// (e.g., "v0.5.1" to "v0.6.0") are only applied under the major policy.
+    pub fn version_req(&self, installed: &Version) -> VersionReq {
+        let op = match self {
+            UpdatePolicy::Patch => Op::Tilde,
+            UpdatePolicy::Minor => Op::Caret,
+            UpdatePolicy::Major => Op::GreaterEq,
+        };
+        VersionReq {
+            comparators: vec![Comparator {
+                op,
+                major: installed.major,
+                minor: Some(installed.minor),
+                patch: Some(installed.patch),
+                pre: installed.pre.clone(),
+            }],
+        }
+    }
+}
+
+/// Represents the weekly time window in which the updater may apply updates.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct MaintenanceWindow {
+    /// Days of the week the window starts on, where 0 is Sunday.
+    /// Leave empty to start the window every day.
+    #[serde(default)]
+    pub weekdays: Vec<u8>,
+    /// Start time in minutes since midnight UTC (e.g., 120 for 02:00 UTC).
+    pub start_minute: u32,
+    pub duration: Duration,
+}
+
+impl MaintenanceWindow {
+    /// Returns true if the unix timestamp in seconds falls within the window.
+    pub fn contains(&self, unix_secs: u64) -> bool {
+        const MINUTES_PER_DAY: u64 = 24 * 60;
+        const MINUTES_PER_WEEK: u64 = 7 * MINUTES_PER_DAY;
+
+        // 1970-01-01 was a Thursday
+        let minute_of_week = (unix_secs / 60 + 4 * MINUTES_PER_DAY) % MINUTES_PER_WEEK;
+        let weekdays: Vec<u64> = if self.weekdays.is_empty() {
+            (0..7).collect()
+        } else {
+            self.weekdays.iter().map(|d| u64::from(*d % 7)).collect()
+        };
+        let duration = self.duration.as_secs() / 60;
+        weekdays.iter().any(|d| {
+            let start = d * MINUTES_PER_DAY + u64::from(self.start_minute);
+            // the window may wrap around to the start of the week
+            (start..start + duration).contains(&minute_of_week)
+                || (start..start + duration).contains(&(minute_of_week + MINUTES_PER_WEEK))
+        })
+    }
+}
+
+/// Callback to restart the node with the applied updates.
+pub type RestartCallback = Arc<dyn Fn(&UpdateReport) -> io::Result<()> + Send + Sync>;
+
+/// Represents how to restart the node after the updater switches versions.
+#[derive(Clone)]
+pub enum RestartHook {
+    /// Runs the command, where the first element is the program
+    /// (e.g., ["systemctl", "restart", "avalanchego"]).
+    Command(Vec<String>),
+    /// Invokes the callback with the update report.
+    Callback(RestartCallback),
+}
+
+impl fmt::Debug for RestartHook {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RestartHook::Command(cmd) => f.debug_tuple("Command").field(cmd).finish(),
+            RestartHook::Callback(_) => f.write_str("Callback"),
+        }
+    }
+}
+
+impl RestartHook {
+    pub async fn invoke(&self, report: &UpdateReport) -> io::Result<()> {
+        match self {
+            RestartHook::Command(cmd) => {
+                let (program, args) = cmd
+                    .split_first()
+                    .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "empty restart command"))?;
+                log::info!("running restart command {:?}", cmd);
+                let status = Command::new(program).args(args).status().await?;
+                if !status.success() {
+                    return Err(Error::new(
+                        ErrorKind::Other,
+                        format!("restart command {:?} failed with {}", cmd, status),
+                    ));
+                }
+                Ok(())
+            }
+            RestartHook::Callback(f) => f(report),
+        }
+    }
+}
+
+/// Represents the "subnet-evm" plugin to keep installed and updated.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct PluginConfig {
+    /// VM ID, which is the plugin binary name in the plugin directory.
+    pub vm_id: String,
+    /// Leave empty to follow the latest release under the update policy.
+    #[serde(default)]
+    pub release_tag: Option<String>,
+}
+
+/// Represents the updater configuration.
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// Root directory of the versioned install layout. See "store::Store".
+    /// New plugins are staged in its "staging" directory until checked.
+    pub root_dir: String,
+    pub plugins: Vec<PluginConfig>,
+    /// How often to check for new releases.
+    pub interval: Duration,
+    pub policy: UpdatePolicy,
+    /// Leave empty to apply updates at any time.
+    pub maintenance_windows: Vec<MaintenanceWindow>,
+    pub restart_hook: Option<RestartHook>,
+}
+
+/// Represents the updates applied in one round.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct UpdateReport {
+    /// Active "avalanchego" release tag before the update, if any.
+    pub previous_tag: Option<String>,
+    /// Active "avalanchego" release tag after the update.
+    pub current_tag: String,
+    /// Set if a new "avalanchego" was installed.
+    pub avalanchego: Option<install::InstallOutcome>,
+    /// Updated plugins by VM ID.
+    pub plugins: Vec<(String, install::InstallOutcome)>,
+}
+
+/// Polls for new releases, installs and verifies them, switches the active
+/// version, and restarts the node via the restart hook.
+pub struct Updater {
+    pub config: Config,
+    pub store: Store,
+}
+
+impl Updater {
+    pub fn new(config: Config) -> Self {
+        let store = Store::new(&config.root_dir);
+        Self { config, store }
+    }
+
+    /// Returns true if updates may be applied at the time.
+    pub fn in_maintenance_window(&self, now: SystemTime) -> bool {
+        if self.config.maintenance_windows.is_empty() {
+            return true;
+        }
+        let unix_secs = now.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        self.config
+            .maintenance_windows
+            .iter()
+            .any(|w| w.contains(unix_secs))
+    }
+
+    /// Runs the update loop forever, checking for updates every interval.
+    /// Failed rounds are logged and retried in the next interval.
+    pub async fn run(&self) -> io::Result<()> {
+        loop {
+            if self.in_maintenance_window(SystemTime::now()) {
+                match self.run_once().await {
+                    Ok(Some(report)) => log::info!("applied update {:?}", report),
+                    Ok(None) => log::info!("no update to apply"),
+                    Err(e) => log::warn!("failed to update ({})", e),
+                }
+            } else {
+                log::info!("outside maintenance windows, skipping update check");
+            }
+            sleep(self.config.interval).await;
+        }
+    }
+
+    /// Checks for updates once, and applies them regardless of the maintenance windows.
+    /// Returns none if nothing was updated.
+    pub async fn run_once(&self) -> io::Result<Option<UpdateReport>> {
+        let previous_tag = self.store.current_tag()?;
+        let tag_name = self
+            .resolve_tag(Project::AvalancheGo, None, previous_tag.as_deref())
+            .await?;
+
+        let mut report = UpdateReport {
+            previous_tag: previous_tag.clone(),
+            current_tag: tag_name.clone(),
+            avalanchego: None,
+            plugins: Vec::new(),
+        };
+        let version_dir = self.store.version_dir(&tag_name)?;
+        let version_dir = version_dir.to_string_lossy().to_string();
+        if previous_tag.as_deref() != Some(tag_name.as_str()) {
+            log::info!(
+                "updating avalanchego from {:?} to {}",
+                previous_tag,
+                tag_name
+            );
+            let outcome = avalanchego::github::ensure_installed(
+                &install::Options {
+                    release_tag: Some(tag_name.clone()),
+                    verify_version: true,
+                    ..Default::default()
+                },
+                &version_dir,
+            )
+            .await?;
+            report.avalanchego = Some(outcome);
+        }
+
+        // new plugins are staged outside of the version directory, which is the
+        // active one if "avalanchego" is unchanged, and only placed once checked
+        let staging_dir = self.store.root_dir.join("staging");
+        let platform = Platform::detect()?;
+        let mut staged = Vec::new();
+        for plugin in self.config.plugins.iter() {
+            let plugin_path = Path::new(&avalanchego::get_plugin_dir(
+                Path::new(&version_dir).join("avalanchego"),
+            ))
+            .join(&plugin.vm_id);
+            // compare against the plugin of the active version,
+            // since a new version directory starts without plugins
+            let active_plugin_path = self.store.current_dir().join("plugins").join(&plugin.vm_id);
+            let installed_tag =
+                install::InstallReceipt::load(&active_plugin_path)?.map(|m| m.tag_name);
+            let plugin_tag = self
+                .resolve_tag(
+                    Project::SubnetEvm,
+                    plugin.release_tag.as_deref(),
+                    installed_tag.as_deref(),
+                )
+                .await?;
+
+            let (installed_path, installed_tag) = (plugin_path.clone(), plugin_tag.clone());
+            let installed = run_blocking(move || {
+                install::find_installed(
+                    &installed_path,
+                    Project::SubnetEvm,
+                    &installed_tag,
+                    platform,
+                )
+            })
+            .await?;
+            if installed.is_some() {
+                continue;
+            }
+
+            fs::create_dir_all(&staging_dir)?;
+            let staged_path = staging_dir.join(&plugin.vm_id);
+            let outcome = subnet_evm::github::ensure_installed(
+                &install::Options {
+                    release_tag: Some(plugin_tag),
+                    verify_version: true,
+                    ..Default::default()
+                },
+                &staged_path.to_string_lossy(),
+            )
+            .await?;
+            report.plugins.push((plugin.vm_id.clone(), outcome));
+            staged.push(plugin_path);
+        }
+
+        if report.avalanchego.is_none() && report.plugins.is_empty() {
+            return Ok(None);
+        }
+        self.check_compatibility(&version_dir, &report).await?;
+
+        for ((vm_id, outcome), plugin_path) in report.plugins.iter_mut().zip(staged) {
+            log::info!(
+                "placing plugin {} {} to {}",
+                vm_id,
+                outcome.tag_name,
+                plugin_path.display()
+            );
+            if let Some(parent_dir) = plugin_path.parent() {
+                fs::create_dir_all(parent_dir)?;
+            }
+            install::copy_file_atomic(&outcome.binary_path, &plugin_path)?;
+            let _ = fs::remove_file(&outcome.binary_path);
+            let _ = fs::remove_file(install::InstallReceipt::path_for(&outcome.binary_path));
+            outcome.binary_path = plugin_path;
+            install::InstallReceipt::record(outcome)?;
+        }
+
+        if report.avalanchego.is_some() {
+            self.store.activate(&tag_name)?;
+        }
+        if let Some(hook) = &self.config.restart_hook {
+            hook.invoke(&report).await?;
+        }
+        Ok(Some(report))
+    }
+
+    /// Returns the release tag to install: the pinned tag if set, otherwise
+    /// the newest stable release that the update policy allows updating
+    /// the installed tag to, otherwise the installed tag.
+    async fn resolve_tag(
+        &self,
+        project: Project,
+        pinned: Option<&str>,
+        installed: Option<&str>,
+    ) -> io::Result<String> {
+        if let Some(v) = pinned {
+            return Ok(v.to_string());
+        }
+
+        let req = match installed {
+            Some(v) => self.config.policy.version_req(&parse_tag(v)?),
+            None => VersionReq::STAR,
+        };
+        let releases = crate::github::fetch_releases(project.org(), project.repo()).await?;
+        match (select_tag(&releases, &req), installed) {
+            (Some(v), _) => Ok(v),
+            (None, Some(installed)) => {
+                log::warn!(
+                    "no stable {} release matches '{}' under {:?} policy, keeping {}",
+                    project,
+                    req,
+                    self.config.policy,
+                    installed
+                );
+                Ok(installed.to_string())
+            }
+            (None, None) => Err(Error::new(
+                ErrorKind::NotFound,
+                format!("no stable {} release found", project),
+            )),
+        }
+    }
+
+    /// Returns an error if any installed plugin speaks a different RPC chain VM
+    /// protocol than "avalanchego", since "avalanchego" would fail to load it.
+    async fn check_compatibility(
+        &self,
+        version_dir: &str,
+        report: &UpdateReport,
+    ) -> io::Result<()> {
+        let rpcchainvm = match &report.avalanchego {
+            Some(outcome) => outcome.version.as_ref().and_then(|v| v.rpcchainvm),
+            None => {
+                let binary_path = Path::new(version_dir).join("avalanchego");
+                run_blocking(move || VersionReport::from_binary(binary_path))
+                    .await
+                    .ok()
+                    .and_then(|v| v.rpcchainvm)
+            }
+        };
+        let rpcchainvm = match rpcchainvm {
+            Some(v) => v,
+            None => return Ok(()),
+        };
+
+        for (vm_id, outcome) in report.plugins.iter() {
+            if let Some(plugin_rpcchainvm) = outcome.version.as_ref().and_then(|v| v.rpcchainvm) {
+                if plugin_rpcchainvm != rpcchainvm {
+                    return Err(Error::new(
+                        ErrorKind::Other,
+                        format!(
+                            "plugin {} {} speaks rpcchainvm {}, but avalanchego {} speaks {}",
+                            vm_id,
+                            outcome.tag_name,
+                            plugin_rpcchainvm,
+                            report.current_tag,
+                            rpcchainvm
+                        ),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-installer --lib -- updater::test_maintenance_window --exact --show-output
+#[test]
+fn test_maintenance_window() {
+    const DAY: u64 = 24 * 60 * 60;
+    const HOUR: u64 = 60 * 60;
+
+    // every day 02:00-03:00 UTC
+    let daily = MaintenanceWindow {
+        weekdays: Vec::new(),
+        start_minute: 120,
+        duration: Duration::from_secs(HOUR),
+    };
+    assert!(daily.contains(2 * HOUR));
+    assert!(daily.contains(5 * DAY + 2 * HOUR + 59 * 60));
+    assert!(!daily.contains(3 * HOUR));
+    assert!(!daily.contains(DAY + HOUR));
+
+    // Thursday 23:00 to Friday 01:00 UTC, where 1970-01-01 was a Thursday
+    let overnight = MaintenanceWindow {
+        weekdays: vec![4],
+        start_minute: 23 * 60,
+        duration: Duration::from_secs(2 * HOUR),
+    };
+    assert!(overnight.contains(23 * HOUR + 30 * 60));
+    assert!(overnight.contains(DAY + 30 * 60));
+    assert!(!overnight.contains(DAY + HOUR));
+    assert!(!overnight.contains(22 * HOUR));
+    // Wednesday and Friday nights of the next week
+    assert!(!overnight.contains(6 * DAY + 23 * HOUR + 30 * 60));
+    assert!(!overnight.contains(8 * DAY + 23 * HOUR + 30 * 60));
+    assert!(overnight.contains(7 * DAY + 23 * HOUR + 30 * 60));
+
+    // Saturday 23:00 to Sunday 01:00 UTC, wrapping around to the start of the week
+    let weekend = MaintenanceWindow {
+        weekdays: vec![6],
+        start_minute: 23 * 60,
+        duration: Duration::from_secs(2 * HOUR),
+    };
+    assert!(weekend.contains(2 * DAY + 23 * HOUR + 30 * 60));
+    assert!(weekend.contains(3 * DAY + 30 * 60));
+    assert!(!weekend.contains(3 * DAY + HOUR + 30 * 60));
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-installer --lib -- updater::test_update_policy --exact --show-output
+#[test]
+fn test_update_policy() {
+    use crate::github::ReleaseResponse;
+
+    for (policy, patch, minor, major) in [
+        (UpdatePolicy::Patch, true, false, false),
+        (UpdatePolicy::Minor, true, true, false),
+        (UpdatePolicy::Major, true, true, true),
+    ] {
+        assert_eq!(policy.allows(UpdateStatus::PatchAvailable), patch);
+        assert_eq!(policy.allows(UpdateStatus::MinorAvailable), minor);
+        assert_eq!(policy.allows(UpdateStatus::MajorAvailable), major);
+        assert!(!policy.allows(UpdateStatus::UpToDate));
+        assert!(!policy.allows(UpdateStatus::InstalledNewer));
+    }
+
+    let release = |tag_name: &str, prerelease: bool| ReleaseResponse {
+        tag_name: Some(tag_name.to_string()),
+        prerelease,
+        ..Default::default()
+    };
+    let releases = vec![
+        release("v2.0.0", false),
+        release("v1.11.2-fuji", true),
+        release("v1.11.1", false),
+        release("v1.10.5", false),
+        release("v1.10.4", false),
+        release("v1.10.2", false),
+        release("v0.6.0", false),
+        release("v0.5.4", false),
+    ];
+    let resolve = |policy: UpdatePolicy, installed: &str| {
+        select_tag(
+            &releases,
+            &policy.version_req(&parse_tag(installed).unwrap()),
+        )
+    };
+    assert_eq!(
+        resolve(UpdatePolicy::Patch, "v1.10.3"),
+        Some("v1.10.5".to_string())
+    );
+    assert_eq!(
+        resolve(UpdatePolicy::Minor, "v1.10.3"),
+        Some("v1.11.1".to_string())
+    );
+    assert_eq!(
+        resolve(UpdatePolicy::Major, "v1.10.3"),
+        Some("v2.0.0".to_string())
+    );
+    // already on the newest allowed release
+    assert_eq!(
+        resolve(UpdatePolicy::Patch, "v1.10.5"),
+        Some("v1.10.5".to_string())
+    );
+    // "^0.5.1" only allows patches
+    assert_eq!(
+        resolve(UpdatePolicy::Minor, "v0.5.1"),
+        Some("v0.5.4".to_string())
+    );
+    assert_eq!(
+        resolve(UpdatePolicy::Major, "v0.5.1"),
+        Some("v2.0.0".to_string())
+    );
+    // never downgrades
+    assert_eq!(resolve(UpdatePolicy::Patch, "v1.10.6"), None);
+    assert_eq!(resolve(UpdatePolicy::Major, "v2.0.1"), None);
+}
@@ -0,0 +1,264 @@
+use std::{
+    io::{self, Error, ErrorKind, Read},
+    path::Path,
+    process::{Command, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+
+/// Represents the version information that a binary reports with "--version".
+///
+/// e.g., "avalanche/1.10.3 [database=v1.4.5, rpcchainvm=26, commit=bd4ce7a, go=1.20.4]"
+/// e.g., "Subnet-EVM/v0.5.1 [AvalancheGo=v1.10.1, rpcchainvm=26]"
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Default)]
+#[serde(rename_all = "snake_case")]
+pub struct VersionReport {
+    /// Name of the binary (e.g., "avalanche", "Subnet-EVM").
+    pub name: String,
+    /// Version of the binary itself, without the "v" prefix (e.g., "1.10.3").
+    pub version: String,
+    /// Database version (e.g., "v1.4.5").
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub database: Option<String>,
+    /// RPC chain VM protocol version (e.g., 26).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rpcchainvm: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    /// Go version used to build the binary (e.g., "1.20.4").
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub go: Option<String>,
+    /// AvalancheGo version that a plugin is built against (e.g., "v1.10.1").
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avalanchego: Option<String>,
+}
+
+/// Maximum time to wait for the binary to print its "--version".
+pub const VERSION_TIMEOUT: Duration = Duration::from_secs(10);
+
+impl VersionReport {
+    /// Parses the "--version" output.
+    pub fn parse(s: &str) -> io::Result<Self> {
+        let s = s.trim();
+        let (head, details) = match s.split_once('[') {
+            Some((head, details)) => (head.trim(), Some(details.trim_end_matches(']'))),
+            None => (s, None),
+        };
+        let (name, version) = head.split_once('/').ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("unexpected version output '{}'", s),
+            )
+        })?;
+        let version = version.trim().trim_start_matches('v');
+        if name.is_empty() || version.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("unexpected version output '{}'", s),
+            ));
+        }
+
+        let mut report = Self {
+            name: name.trim().to_string(),
+            version: version.to_string(),
+            ..Default::default()
+        };
+        for kv in details.unwrap_or_default().split(',') {
+            let (k, v) = match kv.split_once('=') {
+                Some((k, v)) => (k.trim(), v.trim().to_string()),
+                None => continue,
+            };
+            match k.to_lowercase().as_str() {
+                "database" => report.database = Some(v),
+                "rpcchainvm" => {
+                    report.rpcchainvm = Some(v.parse().map_err(|e| {
+                        Error::new(
+                            ErrorKind::InvalidData,
+                            format!("failed to parse rpcchainvm '{}' ({})", v, e),
+                        )
+                    })?)
+                }
+                "commit" => report.commit = Some(v),
+                "go" => report.go = Some(v),
+                "avalanchego" => report.avalanchego = Some(v),
+                _ => log::debug!("skipping unknown version field '{}'", k),
+            }
+        }
+        Ok(report)
+    }
+
+    /// Runs the binary with "--version" and parses its output.
+    /// Kills the binary if it does not exit within "VERSION_TIMEOUT".
+    /// Blocks the calling thread, so async callers should run it with "spawn_blocking".
+    pub fn from_binary<P: AsRef<Path>>(binary_path: P) -> io::Result<Self> {
+        Self::from_binary_with_timeout(binary_path, VERSION_TIMEOUT)
+    }
+
+    /// Same as "from_binary" but with the timeout.
+    pub fn from_binary_with_timeout<P: AsRef<Path>>(
+        binary_path: P,
+        timeout: Duration,
+    ) -> io::Result<Self> {
+        let binary_path = binary_path.as_ref();
+        log::info!("running {} --version", binary_path.display());
+        let mut child = Command::new(binary_path)
+            .arg("--version")
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+
+        // the version output is far smaller than the pipe buffer,
+        // so the pipes are only read once the binary exits
+        let deadline = Instant::now() + timeout;
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+            if Instant::now() >= deadline {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(Error::new(
+                    ErrorKind::TimedOut,
+                    format!(
+                        "'{} --version' did not exit in {:?}",
+                        binary_path.display(),
+                        timeout
+                    ),
+                ));
+            }
+            thread::sleep(Duration::from_millis(20));
+        };
+
+        let mut stdout = String::new();
+        if let Some(mut r) = child.stdout.take() {
+            r.read_to_string(&mut stdout)?;
+        }
+        if !status.success() {
+            let mut stderr = String::new();
+            if let Some(mut r) = child.stderr.take() {
+                r.read_to_string(&mut stderr)?;
+            }
+            return Err(Error::new(
+                ErrorKind::Other,
+                format!(
+                    "'{} --version' failed with {} ({})",
+                    binary_path.display(),
+                    status,
+                    stderr.trim()
+                ),
+            ));
+        }
+        Self::parse(&stdout)
+    }
+
+    /// Returns true if the reported version matches the release tag
+    /// (e.g., "1.10.3" matches "v1.10.3").
+    pub fn matches_tag(&self, tag_name: &str) -> bool {
+        self.version == tag_name.trim_start_matches('v')
+    }
+
+    /// Returns an error if the reported version does not match the release tag.
+    pub fn ensure_matches_tag(&self, tag_name: &str) -> io::Result<()> {
+        if self.matches_tag(tag_name) {
+            return Ok(());
+        }
+        Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "{} reports version '{}', expected '{}'",
+                self.name, self.version, tag_name
+            ),
+        ))
+    }
+}
+
+/// Runs the blocking "f" (e.g., "VersionReport::from_binary") on the blocking
+/// thread pool, so that running the binary does not stall the async runtime.
+pub(crate) async fn run_blocking<T, F>(f: F) -> io::Result<T>
+where
+    F: FnOnce() -> io::Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(f).await.map_err(|e| {
+        Error::new(
+            ErrorKind::Other,
+            format!("failed to join blocking task {}", e),
+        )
+    })?
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-installer --lib -- version::test_version_report --exact --show-output
+#[test]
+fn test_version_report() {
+    use std::{fs, os::unix::fs::PermissionsExt};
+
+    let report = VersionReport::parse(
+        "avalanche/1.10.3 [database=v1.4.5, rpcchainvm=26, commit=bd4ce7a, go=1.20.4]\n",
+    )
+    .unwrap();
+    assert_eq!(
+        report,
+        VersionReport {
+            name: String::from("avalanche"),
+            version: String::from("1.10.3"),
+            database: Some(String::from("v1.4.5")),
+            rpcchainvm: Some(26),
+            commit: Some(String::from("bd4ce7a")),
+            go: Some(String::from("1.20.4")),
+            avalanchego: None,
+        }
+    );
+    assert!(report.matches_tag("v1.10.3"));
+    assert!(report.ensure_matches_tag("v1.10.30").is_err());
+
+    let report =
+        VersionReport::parse("Subnet-EVM/v0.5.1 [AvalancheGo=v1.10.1, rpcchainvm=26]").unwrap();
+    assert_eq!(
+        report,
+        VersionReport {
+            name: String::from("Subnet-EVM"),
+            version: String::from("0.5.1"),
+            rpcchainvm: Some(26),
+            avalanchego: Some(String::from("v1.10.1")),
+            ..Default::default()
+        }
+    );
+    assert!(report.matches_tag("v0.5.1"));
+
+    for s in [
+        "",
+        "avalanche 1.10.3",
+        "avalanche/",
+        "avalanche/1.10.3 [rpcchainvm=latest]",
+    ] {
+        let err = VersionReport::parse(s).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData, "{}", s);
+    }
+
+    let dir = Path::new(&random_manager::tmp_path(10, None).unwrap()).to_path_buf();
+    fs::create_dir_all(&dir).unwrap();
+    let binary_path = dir.join("avalanchego");
+    fs::write(
+        &binary_path,
+        b"#!/bin/sh\necho 'avalanche/1.10.3 [rpcchainvm=26]'\n",
+    )
+    .unwrap();
+    fs::set_permissions(&binary_path, fs::Permissions::from_mode(0o755)).unwrap();
+    let report = VersionReport::from_binary(&binary_path).unwrap();
+    assert_eq!(report.version, "1.10.3");
+
+    // never exits, so gets killed
+    let stalled_path = dir.join("stalled");
+    fs::write(&stalled_path, b"#!/bin/sh\nexec sleep 60\n").unwrap();
+    fs::set_permissions(&stalled_path, fs::Permissions::from_mode(0o755)).unwrap();
+    let started = Instant::now();
+    let err = VersionReport::from_binary_with_timeout(&stalled_path, Duration::from_millis(200))
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::TimedOut);
+    assert!(started.elapsed() < Duration::from_secs(10));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,311 @@
+use std::{
+    env, fs,
+    io::{self, Error, ErrorKind},
+    mem,
+    path::{Path, PathBuf},
+};
+
+/// Prefix of the workspace directory names (e.g., "/tmp/avalanche-installer-abcde12345").
+pub const DIR_NAME_PREFIX: &str = "avalanche-installer-";
+
+/// Represents the temporary directory that owns all temporary files of one
+/// install (e.g., the downloaded archive and the unpacked files).
+/// The directory and everything in it are removed when the workspace is
+/// dropped, including on the error paths, unless "keep" is set.
+#[derive(Debug)]
+pub struct Workspace {
+    dir: PathBuf,
+    keep: bool,
+}
+
+impl Workspace {
+    /// Creates the workspace directory under the "root_dir",
+    /// or under the system temporary directory if none.
+    /// Set "keep" to leave the files behind for debugging.
+    pub fn new(root_dir: Option<&Path>, keep: bool) -> io::Result<Self> {
+        let root_dir = match root_dir {
+            Some(v) => v.to_path_buf(),
+            None => env::temp_dir(),
+        };
+        fs::create_dir_all(&root_dir).map_err(|e| {
+            Error::new(
+                e.kind(),
+                format!(
+                    "failed to create workspace root {} ({})",
+                    root_dir.display(),
+                    e
+                ),
+            )
+        })?;
+
+        let dir = root_dir.join(format!(
+            "{}{}",
+            DIR_NAME_PREFIX,
+            random_manager::secure_string(10)
+        ));
+        fs::create_dir(&dir)?;
+        log::info!("created workspace {}", dir.display());
+        Ok(Self { dir, keep })
+    }
+
+    /// Same as "new", but makes sure the binaries in the workspace can be run.
+    /// If the "root_dir" is on a "noexec" mount (e.g., a hardened "/tmp"),
+    /// the workspace is relocated under the "fallback_root_dir" if set and
+    /// executable, and otherwise fails with "ErrorKind::PermissionDenied".
+    pub fn new_executable(
+        root_dir: Option<&Path>,
+        fallback_root_dir: Option<&Path>,
+        keep: bool,
+    ) -> io::Result<Self> {
+        let workspace = Self::new(root_dir, keep)?;
+        if !is_noexec(workspace.path())? {
+            return Ok(workspace);
+        }
+        let noexec_dir = workspace
+            .path()
+            .parent()
+            .unwrap_or(Path::new("/"))
+            .to_path_buf();
+        drop(workspace);
+
+        if let Some(fallback_root_dir) = fallback_root_dir {
+            let workspace = Self::new(Some(fallback_root_dir), keep)?;
+            if !is_noexec(workspace.path())? {
+                log::warn!(
+                    "workspace root {} is mounted noexec, relocated to {}",
+                    noexec_dir.display(),
+                    fallback_root_dir.display()
+                );
+                return Ok(workspace);
+            }
+            return Err(Error::new(
+                ErrorKind::PermissionDenied,
+                format!(
+                    "workspace roots {} and {} are both mounted noexec, set the workspace root to an executable location",
+                    noexec_dir.display(),
+                    fallback_root_dir.display()
+                ),
+            ));
+        }
+        Err(Error::new(
+            ErrorKind::PermissionDenied,
+            format!(
+                "workspace root {} is mounted noexec, set the workspace root to an executable location",
+                noexec_dir.display()
+            ),
+        ))
+    }
+
+    /// Returns the workspace directory.
+    pub fn path(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the path for the "name" in the workspace.
+    pub fn join<P: AsRef<Path>>(&self, name: P) -> PathBuf {
+        self.dir.join(name)
+    }
+
+    /// Returns the path for the "name" in the workspace, as a string.
+    pub fn join_str<P: AsRef<Path>>(&self, name: P) -> io::Result<String> {
+        let path = self.join(name);
+        path.to_str().map(String::from).ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("non-UTF-8 workspace path {}", path.display()),
+            )
+        })
+    }
+
+    /// Releases the workspace directory to the caller, who then owns
+    /// its cleanup. Returns the directory path.
+    pub fn persist(mut self) -> PathBuf {
+        self.keep = true;
+        mem::take(&mut self.dir)
+    }
+}
+
+impl Drop for Workspace {
+    fn drop(&mut self) {
+        if self.dir.as_os_str().is_empty() {
+            return;
+        }
+        if self.keep {
+            log::info!("keeping workspace {} for debugging", self.dir.display());
+            return;
+        }
+        match fs::remove_dir_all(&self.dir) {
+            Ok(_) => log::info!("removed workspace {}", self.dir.display()),
+            Err(e) if e.kind() == ErrorKind::NotFound => {}
+            Err(e) => log::warn!("failed to remove workspace {} ({})", self.dir.display(), e),
+        }
+    }
+}
+
+/// Returns an error if the binaries in the "dir" cannot be run,
+/// because it is on a "noexec" mount.
+pub fn ensure_executable<P: AsRef<Path>>(dir: P) -> io::Result<()> {
+    let dir = dir.as_ref();
+    if is_noexec(dir)? {
+        return Err(Error::new(
+            ErrorKind::PermissionDenied,
+            format!(
+                "{} is mounted noexec, binaries installed there cannot be run",
+                dir.display()
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Returns true if the "path" is on a filesystem mounted with "noexec",
+/// as listed in "/proc/self/mountinfo". If the "path" does not exist yet,
+/// its nearest existing ancestor is checked. Always false on non-Linux systems.
+pub fn is_noexec<P: AsRef<Path>>(path: P) -> io::Result<bool> {
+    if !cfg!(target_os = "linux") {
+        return Ok(false);
+    }
+
+    let path = existing_ancestor(path.as_ref());
+    if !path.exists() {
+        return Ok(false);
+    }
+    let path = path.canonicalize()?;
+
+    let mountinfo = match fs::read_to_string("/proc/self/mountinfo") {
+        Ok(v) => v,
+        Err(e) => {
+            log::warn!(
+                "failed to read /proc/self/mountinfo ({}), skipping noexec check",
+                e
+            );
+            return Ok(false);
+        }
+    };
+    Ok(mountinfo_is_noexec(&mountinfo, &path))
+}
+
+/// Returns the "path" if it exists, and otherwise its nearest existing ancestor,
+/// falling back to the current directory for relative paths.
+pub(crate) fn existing_ancestor(path: &Path) -> &Path {
+    let mut path = path;
+    if path.as_os_str().is_empty() {
+        path = Path::new(".");
+    }
+    while !path.exists() {
+        path = match path.parent() {
+            Some(v) if !v.as_os_str().is_empty() => v,
+            _ if path != Path::new(".") => Path::new("."),
+            _ => break,
+        };
+    }
+    path
+}
+
+/// Returns true if the innermost mount of the absolute "path"
+/// in the "mountinfo" has the "noexec" option.
+/// ref. <https://man7.org/linux/man-pages/man5/proc.5.html>
+///
+/// e.g., "36 35 98:0 / /tmp rw,nosuid,nodev,noexec,relatime shared:5 - tmpfs tmpfs rw"
+fn mountinfo_is_noexec(mountinfo: &str, path: &Path) -> bool {
+    let mut innermost: Option<(PathBuf, bool)> = None;
+    for line in mountinfo.lines() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() < 6 {
+            continue;
+        }
+        let mount_point = PathBuf::from(unescape_mountinfo(fields[4]));
+        if !path.starts_with(&mount_point) {
+            continue;
+        }
+        let noexec = fields[5].split(',').any(|opt| opt == "noexec");
+        // later entries shadow earlier ones on the same mount point
+        match &innermost {
+            Some((v, _)) if v.components().count() > mount_point.components().count() => {}
+            _ => innermost = Some((mount_point, noexec)),
+        }
+    }
+    innermost.map(|(_, noexec)| noexec).unwrap_or(false)
+}
+
+/// Decodes the octal escapes in the mountinfo paths (e.g., "\040" for a space).
+fn unescape_mountinfo(s: &str) -> String {
+    let mut out = Vec::with_capacity(s.len());
+    let b = s.as_bytes();
+    let mut i = 0;
+    while i < b.len() {
+        if b[i] == b'\\'
+            && i + 3 < b.len()
+            && b[i + 1..i + 4].iter().all(|c| (b'0'..=b'7').contains(c))
+        {
+            out.push((b[i + 1] - b'0') * 64 + (b[i + 2] - b'0') * 8 + (b[i + 3] - b'0'));
+            i += 4;
+        } else {
+            out.push(b[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8_lossy(&out).to_string()
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-installer --lib -- workspace::test_mountinfo_is_noexec --exact --show-output
+#[test]
+fn test_mountinfo_is_noexec() {
+    let mountinfo = "22 1 259:2 / / rw,relatime shared:1 - ext4 /dev/nvme0n1p2 rw
+36 22 0:32 / /tmp rw,nosuid,nodev,noexec,relatime shared:5 - tmpfs tmpfs rw
+37 36 259:3 / /tmp/exec rw,relatime shared:6 - ext4 /dev/nvme0n1p3 rw
+38 22 0:33 / /mnt/my\\040disk rw,noexec shared:7 - ext4 /dev/sdb1 rw
+";
+    for (path, expected) in [
+        ("/", false),
+        ("/home/ubuntu", false),
+        ("/tmp", true),
+        ("/tmp/avalanche-installer-abc", true),
+        ("/tmpfoo", false),
+        ("/tmp/exec/avalanchego", false),
+        ("/mnt/my disk/avalanchego", true),
+    ] {
+        assert_eq!(
+            mountinfo_is_noexec(mountinfo, Path::new(path)),
+            expected,
+            "{}",
+            path
+        );
+    }
+}
+
+/// RUST_LOG=debug cargo test --package avalanche-installer --lib -- workspace::test_workspace --exact --show-output
+#[test]
+fn test_workspace() {
+    let root_dir = Path::new(&random_manager::tmp_path(10, None).unwrap()).to_path_buf();
+    let entries = || fs::read_dir(&root_dir).unwrap().count();
+
+    let ws = Workspace::new(Some(&root_dir), false).unwrap();
+    assert!(ws.path().starts_with(&root_dir));
+    fs::create_dir_all(ws.join("unpacked").join("build")).unwrap();
+    fs::write(ws.join("avalanchego.tar.gz"), b"archive").unwrap();
+    assert_eq!(entries(), 1);
+    drop(ws);
+    assert_eq!(entries(), 0);
+
+    // removed on the error paths too
+    let failing = || -> io::Result<()> {
+        let ws = Workspace::new(Some(&root_dir), false)?;
+        fs::write(ws.join("avalanchego.zip"), b"archive")?;
+        Err(Error::new(ErrorKind::Other, "failed to unpack"))
+    };
+    assert!(failing().is_err());
+    assert_eq!(entries(), 0);
+
+    let ws = Workspace::new(Some(&root_dir), true).unwrap();
+    let kept = ws.path().to_path_buf();
+    drop(ws);
+    assert!(kept.exists());
+
+    let ws = Workspace::new(Some(&root_dir), false).unwrap();
+    let persisted = ws.persist();
+    assert!(persisted.exists());
+    assert_eq!(entries(), 2);
+
+    fs::remove_dir_all(&root_dir).unwrap();
+}